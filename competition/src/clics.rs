@@ -0,0 +1,435 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde_json::{json, Value};
+
+use crate::{Registry, Standings, State, StateResult, Team};
+
+/// Original CLICS ids of participants and tasks.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ClicsIds {
+    pub teams: BTreeMap<u64, String>,
+    pub problems: BTreeMap<u64, String>,
+}
+
+impl ClicsIds {
+    pub fn team(&self, participant: u64) -> String {
+        self.teams.get(&participant).cloned().unwrap_or_else(|| participant.to_string())
+    }
+
+    pub fn problem(&self, task: u64) -> String {
+        self.problems.get(&task).cloned().unwrap_or_else(|| task.to_string())
+    }
+}
+
+/// Contest imported from a CLICS contest API event feed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EventFeed {
+    pub ids: ClicsIds,
+    pub registry: Registry,
+    /// Submissions in contest time order with their latest verdicts.
+    pub states: Vec<State>,
+    /// Line of the feed each of `states` was submitted on.
+    pub lines: Vec<usize>,
+}
+
+#[derive(Debug, Default)]
+struct Submission {
+    team: String,
+    problem: String,
+    time: u64,
+    judgement: Option<String>,
+    line: usize,
+}
+
+#[derive(Debug, Default)]
+struct Feed {
+    teams: BTreeMap<String, Value>,
+    organizations: HashMap<String, Value>,
+    groups: HashMap<String, Value>,
+    problems: BTreeMap<String, Value>,
+    judgement_types: HashMap<String, Value>,
+    submissions: BTreeMap<String, Submission>,
+    /// Submission and judgement type of each judgement, with the number of the event it came in.
+    judgements: HashMap<String, (u64, String, Option<String>)>,
+    events: u64,
+    /// Line of the event being applied.
+    line: usize,
+}
+
+/// Parses CLICS relative time "[-]h:mm:ss[.uuu]" into whole minutes.
+pub fn parse_contest_time(value: &str) -> Result<u64, String> {
+    let error = || format!("Expected contest time as \"h:mm:ss\", found: \"{}\".", value);
+
+    if value.starts_with('-') {
+        return Err(format!("Contest time \"{}\" is before the start of contest.", value));
+    }
+
+    let parts = value
+        .split(':')
+        .map(|v| v.split('.').next().unwrap_or(v).parse::<u64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| error())?;
+
+    match parts[..] {
+        [hours, minutes, _] => Ok(hours * 60 + minutes),
+        _ => Err(error()),
+    }
+}
+
+fn format_contest_time(minutes: u64) -> String {
+    format!("{}:{:02}:00.000", minutes / 60, minutes % 60)
+}
+
+fn string(data: &Value, key: &str) -> Option<String> {
+    match data.get(key)? {
+        Value::String(v) => Some(v.clone()),
+        Value::Number(v) => Some(v.to_string()),
+        _ => None,
+    }
+}
+
+fn display_name(data: &Value) -> Option<String> {
+    string(data, "display_name").or_else(|| string(data, "name"))
+}
+
+fn required(data: &Value, key: &str) -> Result<String, String> {
+    string(data, key).ok_or_else(|| format!("Expected \"{}\" field.", key))
+}
+
+impl Feed {
+    fn apply(&mut self, event: Value) -> Result<(), String> {
+        let kind = event
+            .get("type")
+            .and_then(Value::as_str)
+            .ok_or("Expected \"type\" field.")?
+            .to_string();
+
+        let data = match event.get("data") {
+            Some(Value::Array(items)) => items.clone(),
+            Some(Value::Null) | None => {
+                // Deletion of the object with the event id.
+                if let Some(id) = string(&event, "id") {
+                    self.delete(&kind, &id);
+                }
+                return Ok(());
+            },
+            Some(data) => vec![data.clone()],
+        };
+
+        data.into_iter().try_for_each(|data| self.update(&kind, data))
+    }
+
+    fn delete(&mut self, kind: &str, id: &str) {
+        match kind {
+            "teams" => drop(self.teams.remove(id)),
+            "problems" => drop(self.problems.remove(id)),
+            "submissions" => drop(self.submissions.remove(id)),
+            "judgements" => drop(self.judgements.remove(id)),
+            _ => (),
+        }
+    }
+
+    fn update(&mut self, kind: &str, data: Value) -> Result<(), String> {
+        let id = || required(&data, "id");
+
+        match kind {
+            "teams" => drop(self.teams.insert(id()?, data)),
+            "organizations" => drop(self.organizations.insert(id()?, data)),
+            "groups" => drop(self.groups.insert(id()?, data)),
+            "problems" => drop(self.problems.insert(id()?, data)),
+            "judgement-types" => drop(self.judgement_types.insert(id()?, data)),
+            "submissions" => {
+                let submission = self.submissions.entry(id()?).or_default();
+                submission.team = required(&data, "team_id")?;
+                submission.problem = required(&data, "problem_id")?;
+                submission.time = parse_contest_time(&required(&data, "contest_time")?)?;
+                submission.line = self.line;
+            },
+            "judgements" => {
+                self.events += 1;
+                self.judgements.insert(
+                    id()?,
+                    (self.events, required(&data, "submission_id")?, string(&data, "judgement_type_id")),
+                );
+            },
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    fn result(&self, judgement: Option<&str>) -> StateResult {
+        let judgement = match judgement {
+            Some(judgement) => judgement,
+            None => return StateResult::Unjudged,
+        };
+
+        let flag = |key| self.judgement_types
+            .get(judgement)
+            .and_then(|v| v.get(key))
+            .and_then(Value::as_bool);
+
+        match (flag("solved"), flag("penalty"), judgement) {
+            (Some(true), _, _) | (None, _, "AC") => StateResult::Correct,
+            (Some(false), Some(false), _) | (None, _, "CE") => StateResult::ErroneousSubmission,
+            _ => StateResult::Incorrect,
+        }
+    }
+
+    fn into_event_feed(mut self) -> Result<EventFeed, String> {
+        let mut res = EventFeed::default();
+
+        // Numeric team ids are kept as they are, others get the first free number.
+        let mut participants = HashMap::new();
+        self.teams.keys().filter_map(|id| id.parse::<u64>().ok().map(|v| (id, v))).for_each(|(id, v)| {
+            participants.insert(id.clone(), v);
+        });
+        let mut next = 1;
+        self.teams.keys().for_each(|id| {
+            if !participants.contains_key(id) {
+                while participants.values().any(|&v| v == next) {
+                    next += 1;
+                }
+                participants.insert(id.clone(), next);
+            }
+        });
+
+        res.registry = self.teams
+            .iter()
+            .map(|(id, data)| {
+                let institution = string(data, "organization_id")
+                    .and_then(|v| self.organizations.get(&v))
+                    .and_then(display_name);
+                let division = data
+                    .get("group_ids")
+                    .and_then(Value::as_array)
+                    .and_then(|v| v.first())
+                    .and_then(Value::as_str)
+                    .and_then(|v| self.groups.get(v))
+                    .and_then(display_name);
+
+                Team {
+                    id: participants[id],
+                    name: display_name(data).unwrap_or_else(|| id.clone()),
+                    institution: institution.unwrap_or_default(),
+                    division: division.unwrap_or_default(),
+                }
+            })
+            .collect();
+        res.ids.teams = participants.iter().map(|(id, &v)| (v, id.clone())).collect();
+
+        let mut problems = self.problems.iter().collect::<Vec<_>>();
+        problems.sort_by_key(|(_, data)| data.get("ordinal").and_then(Value::as_i64));
+        let tasks = problems
+            .into_iter()
+            .enumerate()
+            .map(|(idx, (id, _))| (id.clone(), idx as u64 + 1))
+            .collect::<HashMap<_, _>>();
+        res.ids.problems = tasks.iter().map(|(id, &v)| (v, id.clone())).collect();
+
+        // The judgement that came last wins, whatever its id is.
+        let mut judgements = std::mem::take(&mut self.judgements).into_values().collect::<Vec<_>>();
+        judgements.sort_by_key(|(event, ..)| *event);
+        judgements.into_iter().for_each(|(_, submission, judgement)| {
+            if let Some(submission) = self.submissions.get_mut(&submission) {
+                submission.judgement = judgement;
+            }
+        });
+
+        let mut states = self.submissions
+            .values()
+            .map(|v| Ok((v.line, State::new(
+                *participants
+                    .get(&v.team)
+                    .ok_or_else(|| format!("Unknown team \"{}\" in submission.", v.team))?,
+                *tasks
+                    .get(&v.problem)
+                    .ok_or_else(|| format!("Unknown problem \"{}\" in submission.", v.problem))?,
+                v.time,
+                self.result(v.judgement.as_deref()),
+            ))))
+            .collect::<Result<Vec<_>, String>>()?;
+        states.sort_by_key(|(_, v)| v.time);
+        (res.lines, res.states) = states.into_iter().unzip();
+
+        Ok(res)
+    }
+}
+
+impl TryFrom<&str> for EventFeed {
+    type Error = String;
+
+    /// Parses newline delimited JSON events.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut feed = Feed::default();
+
+        value
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .try_for_each(|(idx, line)| serde_json::from_str(line)
+                .map_err(|e| e.to_string())
+                .and_then(|event| {
+                    feed.line = idx + 1;
+                    feed.apply(event)
+                })
+                .map_err(|e| format!("Got an error in event feed on line {}: {}", idx + 1, e)))?;
+
+        feed.into_event_feed()
+    }
+}
+
+/// Scoreboard in the CLICS contest API scoreboard format.
+pub fn scoreboard_json(standings: &Standings) -> Value {
+    let scoreboard = standings.scoreboard;
+    let default = ClicsIds::default();
+    let ids = standings.ids.unwrap_or(&default);
+    let tasks = match ids.problems.is_empty() {
+        true => scoreboard.task_ids(),
+        false => ids.problems.keys().copied().collect(),
+    };
+
+    let rows = standings.ranking
+        .iter()
+        .zip(&standings.ranks)
+        .map(|(total, rank)| json!({
+            "rank": rank,
+            "team_id": ids.team(total.participant),
+            "score": {
+                "num_solved": total.solves,
+                "total_time": total.time,
+            },
+            "problems": tasks
+                .iter()
+                .map(|&task| {
+                    let state = scoreboard.task(total.participant, task).cloned().unwrap_or_default();
+                    let mut res = json!({
+                        "problem_id": ids.problem(task),
+                        "num_judged": state.tries(),
+                        "num_pending": state.pending,
+                        "solved": state.is_solved(),
+                    });
+                    if let Some(time) = state.solved_at {
+                        res["time"] = json!(time);
+                    }
+                    res
+                })
+                .collect::<Vec<_>>(),
+        }))
+        .collect::<Vec<_>>();
+
+    // Only contest times are known, so the state gives them relative to the start
+    // and leaves what can't be told from the scoreboard empty.
+    let frozen = scoreboard.freeze().filter(|_| scoreboard.is_frozen()).map(format_contest_time);
+
+    json!({
+        "contest_time": format_contest_time(scoreboard.last_time()),
+        "state": {
+            "started": format_contest_time(0),
+            "ended": null,
+            "frozen": frozen,
+            "thawed": null,
+            "finalized": null,
+            "end_of_updates": null,
+        },
+        "rows": rows,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::{scoreboard_json, ClicsIds, EventFeed, Scoreboard, ScoringRules, Standings, State, StateResult};
+
+    const FEED: &str = r#"
+{"type": "judgement-types", "id": "AC", "data": {"id": "AC", "name": "correct", "penalty": false, "solved": true}}
+{"type": "judgement-types", "id": "WA", "data": {"id": "WA", "name": "wrong answer", "penalty": true, "solved": false}}
+{"type": "problems", "id": "b", "data": {"id": "b", "label": "B", "ordinal": 2}}
+{"type": "problems", "id": "a", "data": {"id": "a", "label": "A", "ordinal": 1}}
+{"type": "organizations", "id": "omstu", "data": {"id": "omstu", "name": "OmSTU"}}
+{"type": "teams", "id": "5", "data": {"id": "5", "name": "Alpha", "organization_id": "omstu"}}
+{"type": "teams", "id": "t2", "data": {"id": "t2", "name": "Beta"}}
+{"type": "submissions", "id": "s1", "data": {"id": "s1", "team_id": "5", "problem_id": "a", "contest_time": "0:12:30.000"}}
+{"type": "submissions", "id": "s2", "data": {"id": "s2", "team_id": "t2", "problem_id": "b", "contest_time": "1:05:00.000"}}
+{"type": "submissions", "id": "s3", "data": {"id": "s3", "team_id": "5", "problem_id": "a", "contest_time": "0:30:59.999"}}
+{"type": "judgements", "id": "j1", "data": {"id": "j1", "submission_id": "s1", "judgement_type_id": "WA"}}
+{"type": "judgements", "id": "j2", "data": {"id": "j2", "submission_id": "s2", "judgement_type_id": "AC"}}
+{"type": "judgements", "id": "j3", "data": {"id": "j3", "submission_id": "s3", "judgement_type_id": "AC"}}
+"#;
+
+    #[test]
+    fn test_import_event_feed() {
+        let feed = EventFeed::try_from(FEED).unwrap();
+
+        assert_eq!(feed.states, vec![
+            State::new(5, 1, 12, StateResult::Incorrect),
+            State::new(5, 1, 30, StateResult::Correct),
+            State::new(1, 2, 65, StateResult::Correct),
+        ]);
+        assert_eq!(feed.lines, vec![9, 11, 10]);
+        assert_eq!(feed.ids.team(1), "t2");
+        assert_eq!(feed.ids.problem(2), "b");
+        assert_eq!(feed.registry.get(5).map(|v| v.institution.as_str()), Some("OmSTU"));
+    }
+
+    #[test]
+    fn test_judgements_in_feed_order() {
+        let feed = FEED.to_string() + r#"
+{"type": "submissions", "id": "s4", "data": {"id": "s4", "team_id": "t2", "problem_id": "a", "contest_time": "1:10:00.000"}}
+{"type": "judgements", "id": "j9", "data": {"id": "j9", "submission_id": "s4", "judgement_type_id": "WA"}}
+{"type": "judgements", "id": "j10", "data": {"id": "j10", "submission_id": "s4", "judgement_type_id": "AC"}}
+"#;
+        let feed = EventFeed::try_from(feed.as_str()).unwrap();
+
+        assert_eq!(feed.states.last(), Some(&State::new(1, 1, 70, StateResult::Correct)));
+    }
+
+    #[test]
+    fn test_export_scoreboard() {
+        let mut scoreboard = Scoreboard::new(ScoringRules::Classic);
+        scoreboard.apply(State::new(1, 1, 10, StateResult::Incorrect));
+        scoreboard.apply(State::new(1, 1, 15, StateResult::Correct));
+
+        let ids = ClicsIds {
+            teams: [(1, "team-1".to_string())].into(),
+            problems: [(1, "a".to_string()), (2, "b".to_string())].into(),
+        };
+        let mut standings = Standings::new(&scoreboard);
+        standings.set_ids(Some(&ids));
+
+        assert_eq!(scoreboard_json(&standings), json!({
+            "contest_time": "0:15:00.000",
+            "state": {
+                "started": "0:00:00.000",
+                "ended": null,
+                "frozen": null,
+                "thawed": null,
+                "finalized": null,
+                "end_of_updates": null,
+            },
+            "rows": [{
+                "rank": 1,
+                "team_id": "team-1",
+                "score": { "num_solved": 1, "total_time": 35 },
+                "problems": [
+                    { "problem_id": "a", "num_judged": 2, "num_pending": 0, "solved": true, "time": 15 },
+                    { "problem_id": "b", "num_judged": 0, "num_pending": 0, "solved": false },
+                ],
+            }],
+        }));
+    }
+
+    #[test]
+    fn test_export_frozen_state() {
+        let mut scoreboard = Scoreboard::with_freeze(ScoringRules::Classic, 60);
+        scoreboard.apply(State::new(1, 1, 10, StateResult::Correct));
+        assert_eq!(scoreboard_json(&Standings::new(&scoreboard))["state"]["frozen"], json!(null));
+
+        scoreboard.apply(State::new(1, 2, 75, StateResult::Correct));
+        assert_eq!(scoreboard_json(&Standings::new(&scoreboard))["state"]["frozen"], json!("1:00:00.000"));
+
+        scoreboard.unfreeze();
+        assert_eq!(scoreboard_json(&Standings::new(&scoreboard))["state"]["frozen"], json!(null));
+    }
+}
@@ -0,0 +1,220 @@
+use std::convert::TryFrom;
+
+const SECONDS_IN_DAY: i64 = 24 * 60 * 60;
+
+/// Units of contest time converted from timestamps.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// Whole minutes since the start, rounded down.
+    #[default]
+    Minutes,
+    /// Seconds since the start, every other time is scaled to seconds as well.
+    Seconds,
+}
+
+impl Rounding {
+    /// Units of contest time in a minute.
+    pub fn per_minute(self) -> u64 {
+        match self {
+            Rounding::Minutes => 1,
+            Rounding::Seconds => 60,
+        }
+    }
+}
+
+impl TryFrom<&str> for Rounding {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "minutes" => Ok(Rounding::Minutes),
+            "seconds" => Ok(Rounding::Seconds),
+            _ => Err(format!("Unknown rounding \"{}\".", value)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Timestamp {
+    /// Seconds since midnight of "HH:MM:SS".
+    TimeOfDay(i64),
+    /// Seconds since the Unix epoch of ISO-8601 "YYYY-MM-DDTHH:MM:SS[.fff][Z|+HH:MM]".
+    Absolute(i64),
+}
+
+fn number(value: &str, max: i64) -> Option<i64> {
+    value
+        .parse()
+        .ok()
+        .filter(|v| (0..max).contains(v) && value.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Seconds of "HH:MM:SS", fractions of a second are dropped.
+fn parse_time_of_day(value: &str) -> Option<i64> {
+    let value = value.split('.').next()?;
+
+    match value.split(':').collect::<Vec<_>>()[..] {
+        [hours, minutes, seconds] => Some(number(hours, 24)? * 3600 + number(minutes, 60)? * 60 + number(seconds, 60)?),
+        _ => None,
+    }
+}
+
+/// Days since the Unix epoch of a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+fn parse_absolute(value: &str) -> Option<i64> {
+    let (date, time) = value.split_once('T')?;
+
+    let date = match date.split('-').collect::<Vec<_>>()[..] {
+        [year, month, day] if year.len() == 4 => days_from_civil(
+            number(year, 10000)?,
+            number(month, 13).filter(|&v| v > 0)?,
+            number(day, 32).filter(|&v| v > 0)?,
+        ),
+        _ => return None,
+    };
+
+    let (time, offset) = match time.find(['Z', '+', '-']) {
+        Some(idx) => time.split_at(idx),
+        None => (time, ""),
+    };
+
+    let offset = match offset {
+        "" | "Z" => 0,
+        offset => {
+            let (hours, minutes) = offset[1..].split_once(':')?;
+            let seconds = number(hours, 24)? * 3600 + number(minutes, 60)? * 60;
+            if offset.starts_with('-') { -seconds } else { seconds }
+        },
+    };
+
+    Some(date * SECONDS_IN_DAY + parse_time_of_day(time)? - offset)
+}
+
+impl TryFrom<&str> for Timestamp {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let res = match value.contains('T') {
+            true => parse_absolute(value).map(Timestamp::Absolute),
+            false => parse_time_of_day(value).map(Timestamp::TimeOfDay),
+        };
+
+        res.ok_or_else(|| format!("Expected time as \"HH:MM:SS\" or ISO-8601 timestamp, found: \"{}\".", value))
+    }
+}
+
+/// Converts absolute submission timestamps to contest time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clock {
+    start: Timestamp,
+    /// Contest length in seconds.
+    length: Option<i64>,
+    rounding: Rounding,
+}
+
+impl Clock {
+    /// Contest starting at "HH:MM:SS" or ISO-8601 `start`.
+    pub fn new(start: &str, rounding: Rounding) -> Result<Self, String> {
+        Ok(Self {
+            start: start.try_into()?,
+            length: None,
+            rounding,
+        })
+    }
+
+    /// Submissions later than `length` minutes after the start are rejected.
+    pub fn set_length(&mut self, length: Option<u64>) {
+        self.length = length.map(|v| v as i64 * 60);
+    }
+
+    pub fn rounding(&self) -> Rounding {
+        self.rounding
+    }
+
+    /// Contest time of a timestamp of the same kind as the start.
+    /// Plain numbers are taken as contest minutes.
+    pub fn time(&self, value: &str) -> Result<u64, String> {
+        if let Ok(time) = value.parse::<u64>() {
+            return Ok(time * self.rounding.per_minute());
+        }
+
+        let offset = match (self.start, Timestamp::try_from(value)?) {
+            // Times of day carry no date: an earlier one is taken for the next day only
+            // when the contest goes past midnight and is still running then.
+            (Timestamp::TimeOfDay(start), Timestamp::TimeOfDay(time)) => match time - start {
+                offset if offset < 0 && self.length.is_some_and(|length| offset + SECONDS_IN_DAY <= length) =>
+                    offset + SECONDS_IN_DAY,
+                offset => offset,
+            },
+            (Timestamp::Absolute(start), Timestamp::Absolute(time)) => time - start,
+            _ => return Err(format!("Expected \"{}\" to be the same kind of timestamp as the contest start.", value)),
+        };
+
+        if offset < 0 {
+            return Err(format!("Submission at \"{}\" is before the start of contest.", value));
+        }
+        if self.length.is_some_and(|length| offset > length) {
+            return Err(format!("Submission at \"{}\" is after the end of contest.", value));
+        }
+
+        Ok(match self.rounding {
+            Rounding::Minutes => offset as u64 / 60,
+            Rounding::Seconds => offset as u64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Clock, Rounding};
+
+    #[test]
+    fn test_clock_time_of_day() {
+        let mut clock = Clock::new("10:00:00", Rounding::Minutes).unwrap();
+        clock.set_length(Some(300));
+
+        assert_eq!(clock.time("10:21:59"), Ok(21));
+        assert_eq!(clock.time("15:00:00"), Ok(300));
+        assert_eq!(clock.time("42"), Ok(42));
+        assert!(clock.time("09:59:59").is_err_and(|e| e.contains("before the start")));
+        assert!(clock.time("15:00:01").is_err());
+        assert!(clock.time("10:61:00").is_err());
+        assert!(clock.time("2024-05-01T10:00:00Z").is_err());
+    }
+
+    #[test]
+    fn test_clock_past_midnight() {
+        let mut clock = Clock::new("22:00:00", Rounding::Minutes).unwrap();
+        clock.set_length(Some(300));
+
+        assert_eq!(clock.time("23:59:00"), Ok(119));
+        assert_eq!(clock.time("00:30:00"), Ok(150));
+        assert_eq!(clock.time("03:00:00"), Ok(300));
+        assert!(clock.time("03:00:01").is_err());
+        assert!(clock.time("21:59:59").is_err_and(|e| e.contains("before the start")));
+
+        // Without a length the contest is never taken to go past midnight.
+        clock.set_length(None);
+        assert!(clock.time("00:30:00").is_err_and(|e| e.contains("before the start")));
+    }
+
+    #[test]
+    fn test_clock_absolute() {
+        let clock = Clock::new("2024-02-28T23:30:00+03:00", Rounding::Seconds).unwrap();
+
+        assert_eq!(clock.time("2024-02-28T20:30:15Z"), Ok(15));
+        assert_eq!(clock.time("2024-02-29T20:30:00.999Z"), Ok(24 * 60 * 60));
+        assert_eq!(clock.time("2"), Ok(120));
+        assert_eq!(clock.time("2024-03-01T00:30:00+03:00"), Ok(25 * 60 * 60));
+        assert!(clock.time("2024-02-28T20:29:59Z").is_err());
+        assert!(clock.time("2024-02-28 20:30:00").is_err());
+    }
+}
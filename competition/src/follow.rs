@@ -0,0 +1,175 @@
+use std::{collections::HashMap, io::{BufRead, Write}, thread, time::Duration};
+
+use crate::{Clock, Format, Registry, Scoreboard, Standings, State, Total};
+
+/// Reads submissions from a log that keeps growing and emits the ranking after each change.
+pub struct Follower<R, W> {
+    reader: R,
+    sink: W,
+    scoreboard: Scoreboard,
+    format: Format,
+    changes_only: bool,
+    registry: Option<Registry>,
+    clock: Option<Clock>,
+    line: usize,
+    buffer: String,
+    emitted: HashMap<u64, (usize, Total)>,
+}
+
+impl<R: BufRead, W: Write> Follower<R, W> {
+    pub fn new(reader: R, sink: W, scoreboard: Scoreboard, format: Format) -> Self {
+        Self {
+            reader,
+            sink,
+            scoreboard,
+            format,
+            changes_only: false,
+            registry: None,
+            clock: None,
+            line: 0,
+            buffer: String::new(),
+            emitted: HashMap::new(),
+        }
+    }
+
+    /// Emit only "rank participant solves time" lines of participants whose row changed.
+    pub fn set_changes_only(&mut self, changes_only: bool) {
+        self.changes_only = changes_only;
+    }
+
+    /// Show team names and fail on participants missing from the registry.
+    pub fn set_registry(&mut self, registry: Option<Registry>) {
+        self.registry = registry;
+    }
+
+    /// Convert submission timestamps to contest time with `clock`.
+    pub fn set_clock(&mut self, clock: Option<Clock>) {
+        self.clock = clock;
+    }
+
+    pub fn scoreboard(&self) -> &Scoreboard {
+        &self.scoreboard
+    }
+
+    /// Applies all complete lines available right now, emitting the ranking if any were read.
+    /// Returns the number of applied submissions.
+    pub fn poll(&mut self) -> Result<usize, String> {
+        let mut applied = 0;
+
+        loop {
+            let read = self.reader
+                .read_line(&mut self.buffer)
+                .map_err(|e| format!("Got an io error: \"{}\"", e))?;
+
+            // A line without its terminator is still being written, keep it for the next poll.
+            if read == 0 || !self.buffer.ends_with('\n') {
+                break;
+            }
+
+            self.line += 1;
+            let line = std::mem::take(&mut self.buffer);
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let state = match &self.clock {
+                Some(clock) => State::parse_with(line.trim(), |v| clock.time(v)),
+                None => State::try_from(line.trim()),
+            };
+            let state = state.map_err(|e| format!("line {}: {}", self.line, e))?;
+            self.scoreboard.apply(state);
+            applied += 1;
+        }
+
+        if applied > 0 {
+            self.emit()?;
+        }
+
+        Ok(applied)
+    }
+
+    /// Polls the log forever, sleeping `interval` between polls.
+    pub fn run(&mut self, interval: Duration) -> Result<(), String> {
+        loop {
+            self.poll()?;
+            thread::sleep(interval);
+        }
+    }
+
+    fn emit(&mut self) -> Result<(), String> {
+        if let Some(registry) = &self.registry {
+            registry.check(&self.scoreboard)?;
+        }
+
+        let output = if self.changes_only {
+            self.changed_rows()
+        } else {
+            let mut standings = Standings::new(&self.scoreboard);
+            standings.set_registry(self.registry.as_ref());
+            self.format.render_standings(&standings)
+        };
+
+        writeln!(self.sink, "{}", output)
+            .and_then(|_| self.sink.flush())
+            .map_err(|e| format!("Got an io error: \"{}\"", e))
+    }
+
+    fn changed_rows(&mut self) -> String {
+        self.scoreboard
+            .ranks()
+            .into_iter()
+            .filter_map(|row| {
+                let changed = self.emitted
+                    .get(&row.1.participant)
+                    .is_none_or(|old| old.0 != row.0 || old.1.cmp_results(&row.1).is_ne());
+
+                changed.then(|| {
+                    let line = match &self.registry {
+                        Some(registry) => format!("{} {} {} {}\n", row.0, registry.name(row.1.participant), row.1.solves, row.1.time),
+                        None => format!("{} {}\n", row.0, row.1),
+                    };
+                    self.emitted.insert(row.1.participant, row);
+                    line
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs::{File, OpenOptions}, io::{BufReader, Write}};
+
+    use crate::{Follower, Format, Scoreboard, ScoringRules};
+
+    #[test]
+    fn test_follow_growing_log() {
+        let path = std::env::temp_dir().join(format!("competition-follow-{}.log", std::process::id()));
+        let mut log = File::create(&path).unwrap();
+        let mut output = vec![];
+
+        {
+            let reader = BufReader::new(File::open(&path).unwrap());
+            let mut follower = Follower::new(reader, &mut output, Scoreboard::new(ScoringRules::Classic), Format::Compact);
+            follower.set_changes_only(true);
+
+            assert_eq!(follower.poll(), Ok(0));
+
+            write!(log, "1 1 10 C\n2 1 20 C\n3 1 3").unwrap();
+            assert_eq!(follower.poll(), Ok(2));
+
+            writeln!(log, "0 C").unwrap();
+            let mut log = OpenOptions::new().append(true).open(&path).unwrap();
+            writeln!(log, "2 2 40 C").unwrap();
+            assert_eq!(follower.poll(), Ok(2));
+            assert_eq!(follower.poll(), Ok(0));
+        }
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "1 1 1 10\n2 2 1 20\n\n1 2 2 60\n2 1 1 10\n3 3 1 30\n\n",
+        );
+    }
+}
@@ -0,0 +1,280 @@
+use std::{convert::TryFrom, fmt::Write};
+
+use crate::{scoreboard_json, Scoreboard, Standings, TaskState};
+
+/// Output format of a scoreboard.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// One "participant solves time" line per participant.
+    #[default]
+    Compact,
+    /// Aligned per-task matrix.
+    Text,
+    Csv,
+    Html,
+    /// CLICS contest API scoreboard.
+    Json,
+}
+
+impl TryFrom<&str> for Format {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "compact" => Ok(Format::Compact),
+            "text" => Ok(Format::Text),
+            "csv" => Ok(Format::Csv),
+            "html" => Ok(Format::Html),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("Unknown output format \"{}\".", value)),
+        }
+    }
+}
+
+impl Format {
+    pub fn render(&self, scoreboard: &Scoreboard) -> String {
+        self.render_standings(&Standings::new(scoreboard))
+    }
+
+    pub fn render_standings(&self, standings: &Standings) -> String {
+        match self {
+            Format::Compact => render_compact(standings),
+            Format::Text => render_text(standings),
+            Format::Csv => render_csv(standings),
+            Format::Html => render_html(standings, None),
+            Format::Json => format!("{:#}\n", scoreboard_json(standings)),
+        }
+    }
+}
+
+/// Cell of the matrix: "attempts/minute/penalty", with pending submissions after "?".
+fn cell(scoreboard: &Scoreboard, task: Option<&TaskState>) -> String {
+    let task = match task {
+        Some(task) => task,
+        None => return "-".into(),
+    };
+
+    let mut res = format!(
+        "{}/{}/{}",
+        task.tries(),
+        task.solved_at.map_or("-".into(), |v| v.to_string()),
+        task.penalty(scoreboard.rules()),
+    );
+
+    if task.pending > 0 {
+        let _ = write!(res, "?{}", task.pending);
+    }
+
+    res
+}
+
+fn medal(standings: &Standings, idx: usize) -> String {
+    standings.medal(idx).map_or(String::new(), |v| v.to_string())
+}
+
+fn render_compact(standings: &Standings) -> String {
+    standings.ranking
+        .iter()
+        .enumerate()
+        .fold(String::new(), |mut res, (idx, v)| {
+            let _ = write!(res, "{} {} {}", standings.name(v.participant), v.solves, v.time);
+            if let Some(medal) = standings.medal(idx) {
+                let _ = write!(res, " {}", medal);
+            }
+            res.push('\n');
+            res
+        })
+}
+
+fn rows(standings: &Standings, tasks: &[u64]) -> Vec<Vec<String>> {
+    let scoreboard = standings.scoreboard;
+
+    standings.ranking
+        .iter()
+        .enumerate()
+        .map(|(idx, total)| {
+            let mut row = vec![standings.ranks[idx].to_string(), standings.name(total.participant)];
+            row.extend(tasks
+                .iter()
+                .map(|&task| cell(scoreboard, scoreboard.task(total.participant, task))));
+            row.push(total.solves.to_string());
+            row.push(total.time.to_string());
+            if !standings.medals.is_empty() {
+                row.push(medal(standings, idx));
+            }
+            row
+        })
+        .collect()
+}
+
+fn header(standings: &Standings, tasks: &[u64]) -> Vec<String> {
+    let mut res = vec!["rank".to_string(), "participant".to_string()];
+    res.extend(tasks.iter().map(|v| v.to_string()));
+    res.push("solves".into());
+    res.push("time".into());
+    if !standings.medals.is_empty() {
+        res.push("medal".into());
+    }
+    res
+}
+
+fn render_text(standings: &Standings) -> String {
+    let tasks = standings.scoreboard.task_ids();
+    let mut table = vec![header(standings, &tasks)];
+    table.extend(rows(standings, &tasks));
+
+    let widths = (0..table[0].len())
+        .map(|i| table.iter().map(|row| row[i].chars().count()).max().unwrap_or(0))
+        .collect::<Vec<_>>();
+
+    table.iter().fold(String::new(), |mut res, row| {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(v, width)| format!("{:>width$}", v, width = width))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let _ = writeln!(res, "{}", line.trim_end());
+        res
+    })
+}
+
+fn render_csv(standings: &Standings) -> String {
+    let scoreboard = standings.scoreboard;
+    let tasks = scoreboard.task_ids();
+
+    let mut res = "rank,participant".to_string();
+    tasks.iter().for_each(|task| {
+        let _ = write!(res, ",{0} attempts,{0} time,{0} penalty,{0} pending", task);
+    });
+    res.push_str(",solves,time");
+    if !standings.medals.is_empty() {
+        res.push_str(",medal");
+    }
+    res.push('\n');
+
+    standings.ranking.iter().enumerate().for_each(|(idx, total)| {
+        let _ = write!(res, "{},{}", standings.ranks[idx], escape_csv(&standings.name(total.participant)));
+        tasks.iter().for_each(|&task| {
+            let _ = match scoreboard.task(total.participant, task) {
+                Some(task) => write!(
+                    res,
+                    ",{},{},{},{}",
+                    task.tries(),
+                    task.solved_at.map_or(String::new(), |v| v.to_string()),
+                    task.penalty(scoreboard.rules()),
+                    task.pending,
+                ),
+                None => write!(res, ",0,,0,0"),
+            };
+        });
+        let _ = write!(res, ",{},{}", total.solves, total.time);
+        if !standings.medals.is_empty() {
+            let _ = write!(res, ",{}", medal(standings, idx));
+        }
+        res.push('\n');
+    });
+
+    res
+}
+
+fn escape_csv(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.into()
+    }
+}
+
+pub(crate) fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Standalone HTML page which reloads itself every `refresh` seconds if set.
+pub(crate) fn render_html(standings: &Standings, refresh: Option<u64>) -> String {
+    let tasks = standings.scoreboard.task_ids();
+    let mut res = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    if let Some(refresh) = refresh {
+        let _ = writeln!(res, "<meta http-equiv=\"refresh\" content=\"{}\">", refresh);
+    }
+    res.push_str("<title>Scoreboard</title>\n</head>\n<body>\n<table>\n");
+
+    let _ = writeln!(
+        res,
+        "<tr>{}</tr>",
+        header(standings, &tasks)
+            .iter()
+            .map(|v| format!("<th>{}</th>", escape_html(v)))
+            .collect::<String>(),
+    );
+
+    rows(standings, &tasks).iter().for_each(|row| {
+        let _ = writeln!(
+            res,
+            "<tr>{}</tr>",
+            row.iter()
+                .map(|v| format!("<td>{}</td>", escape_html(v)))
+                .collect::<String>(),
+        );
+    });
+
+    res.push_str("</table>\n</body>\n</html>\n");
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Format, Medals, Scoreboard, ScoringRules, Standings, State};
+
+    fn scoreboard() -> Scoreboard {
+        let mut scoreboard = Scoreboard::new(ScoringRules::Classic);
+
+        [
+            "1 1 10 I",
+            "1 1 30 C",
+            "2 2 50 I",
+        ]
+        .into_iter()
+        .for_each(|v| scoreboard.apply(State::try_from(v).unwrap()));
+
+        scoreboard
+    }
+
+    #[test]
+    fn test_render_text() {
+        assert_eq!(
+            Format::Text.render(&scoreboard()),
+            concat!(
+                "rank participant       1     2 solves time\n",
+                "   1           1 2/30/20     -      1   50\n",
+                "   2           2       - 1/-/0      0    0\n",
+            ),
+        );
+    }
+
+    #[test]
+    fn test_render_medals() {
+        let scoreboard = scoreboard();
+        let mut standings = Standings::new(&scoreboard);
+        standings.set_medals(&Medals::try_from("1,0,0").unwrap());
+
+        assert_eq!(Format::Compact.render_standings(&standings), "1 1 50 gold\n2 0 0\n");
+    }
+
+    #[test]
+    fn test_render_csv() {
+        assert_eq!(
+            Format::Csv.render(&scoreboard()),
+            concat!(
+                "rank,participant,1 attempts,1 time,1 penalty,1 pending,",
+                "2 attempts,2 time,2 penalty,2 pending,solves,time\n",
+                "1,1,2,30,20,0,0,,0,0,1,50\n",
+                "2,2,0,,0,0,1,,0,0,0,0\n",
+            ),
+        );
+    }
+}
@@ -0,0 +1,140 @@
+use std::fmt::Write;
+
+use crate::{Scoreboard, ScoringRules, State, Total};
+
+/// Ranking right after all submissions made at `time`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub time: u64,
+    pub ranks: Vec<(usize, Total)>,
+}
+
+impl Snapshot {
+    pub fn rank_of(&self, participant: u64) -> Option<usize> {
+        self.ranks.iter().find(|(_, v)| v.participant == participant).map(|(rank, _)| *rank)
+    }
+
+    fn same_ranking(&self, ranks: &[(usize, Total)]) -> bool {
+        self.ranks.len() == ranks.len()
+            && self.ranks.iter().zip(ranks).all(|(l, r)| {
+                l.0 == r.0 && l.1.participant == r.1.participant && l.1.cmp_results(&r.1).is_eq()
+            })
+    }
+}
+
+/// Scoreboard replayed in time order with a snapshot at each change of the ranking.
+#[derive(Debug, Clone)]
+pub struct History {
+    rules: ScoringRules,
+    states: Vec<State>,
+    snapshots: Vec<Snapshot>,
+}
+
+impl History {
+    pub fn new(rules: ScoringRules, states: impl IntoIterator<Item = State>) -> Self {
+        let mut states = states.into_iter().collect::<Vec<_>>();
+        states.sort_by_key(|v| v.time);
+
+        let mut scoreboard = Scoreboard::new(rules);
+        let mut snapshots: Vec<Snapshot> = vec![];
+
+        states.chunk_by(|l, r| l.time == r.time).for_each(|chunk| {
+            chunk.iter().for_each(|v| scoreboard.apply(v.clone()));
+
+            let ranks = scoreboard.ranks();
+            if snapshots.last().is_none_or(|v| !v.same_ranking(&ranks)) {
+                snapshots.push(Snapshot {
+                    time: chunk[0].time,
+                    ranks,
+                });
+            }
+        });
+
+        Self {
+            rules,
+            states,
+            snapshots,
+        }
+    }
+
+    pub fn snapshots(&self) -> &[Snapshot] {
+        &self.snapshots
+    }
+
+    /// Latest snapshot made at or before `time`.
+    pub fn at(&self, time: u64) -> Option<&Snapshot> {
+        let idx = self.snapshots.partition_point(|v| v.time <= time);
+        idx.checked_sub(1).map(|idx| &self.snapshots[idx])
+    }
+
+    /// Scoreboard with all submissions made at or before `time`.
+    pub fn scoreboard_at(&self, time: u64) -> Scoreboard {
+        let mut res = Scoreboard::new(self.rules);
+        self.states
+            .iter()
+            .take_while(|v| v.time <= time)
+            .for_each(|v| res.apply(v.clone()));
+        res
+    }
+
+    /// Rank of the participant at each snapshot where it was ranked.
+    pub fn series(&self, participant: u64) -> Vec<(u64, usize)> {
+        self.snapshots
+            .iter()
+            .filter_map(|v| v.rank_of(participant).map(|rank| (v.time, rank)))
+            .collect()
+    }
+
+    /// All snapshots as "time,participant,rank,solves,penalty" rows.
+    pub fn to_csv(&self) -> String {
+        self.snapshots.iter().fold(
+            "time,participant,rank,solves,penalty\n".to_string(),
+            |mut res, snapshot| {
+                snapshot.ranks.iter().for_each(|(rank, total)| {
+                    let _ = writeln!(res, "{},{},{},{},{}", snapshot.time, total.participant, rank, total.solves, total.time);
+                });
+                res
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{History, ScoringRules, State};
+
+    fn history() -> History {
+        History::new(
+            ScoringRules::Classic,
+            [
+                "2 1 50 C",
+                "1 1 10 C",
+                "1 2 30 I",
+                "2 2 50 C",
+                "1 2 137 C",
+            ]
+            .into_iter()
+            .map(|v| State::try_from(v).unwrap()),
+        )
+    }
+
+    #[test]
+    fn test_history_snapshots() {
+        let history = history();
+
+        assert_eq!(history.snapshots().iter().map(|v| v.time).collect::<Vec<_>>(), vec![10, 50, 137]);
+        assert_eq!(history.series(1), vec![(10, 1), (50, 2), (137, 2)]);
+        assert_eq!(history.series(2), vec![(50, 1), (137, 1)]);
+        assert_eq!(history.at(100).map(|v| v.time), Some(50));
+        assert_eq!(history.at(5), None);
+        assert_eq!(history.scoreboard_at(136).rank_of(2), Some(1));
+    }
+
+    #[test]
+    fn test_history_csv() {
+        assert_eq!(
+            history().to_csv().lines().take(3).collect::<Vec<_>>(),
+            vec!["time,participant,rank,solves,penalty", "10,1,1,1,10", "50,2,1,2,100"],
+        );
+    }
+}
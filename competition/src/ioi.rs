@@ -0,0 +1,245 @@
+use std::{cmp::Ordering, collections::HashMap, fmt::Display};
+
+use crate::{State, StateResult};
+
+/// Points of an accepted submission without a score.
+const FULL_SCORE: u64 = 100;
+
+/// How the score of a task is taken from its submissions.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TaskScoring {
+    /// Best total score of a single submission.
+    #[default]
+    Max,
+    /// Sum of the best scores of each subtask over all submissions.
+    Subtasks,
+}
+
+impl TryFrom<&str> for TaskScoring {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "max" => Ok(TaskScoring::Max),
+            "subtasks" => Ok(TaskScoring::Subtasks),
+            _ => Err(format!("Unknown task scoring \"{}\".", value)),
+        }
+    }
+}
+
+/// How participants with equal scores are ordered.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Equal scores share the rank.
+    #[default]
+    None,
+    /// Earlier time of the last score improvement wins.
+    LastImprovement,
+    /// Fewer scored submissions win.
+    Submissions,
+}
+
+impl TryFrom<&str> for TieBreak {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "none" => Ok(TieBreak::None),
+            "last" => Ok(TieBreak::LastImprovement),
+            "submissions" => Ok(TieBreak::Submissions),
+            _ => Err(format!("Unknown tie-break \"{}\".", value)),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IoiTask {
+    pub score: u64,
+    /// Best score of each subtask.
+    pub subtasks: Vec<u64>,
+    pub submissions: u64,
+    /// Time the task score was last improved at.
+    pub last_improvement: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IoiTotal {
+    pub participant: u64,
+    pub score: u64,
+    pub submissions: u64,
+    pub last_improvement: u64,
+}
+
+impl Display for IoiTotal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{} {}", self.participant, self.score))
+    }
+}
+
+/// Standings of a contest with partial scoring.
+#[derive(Debug, Default, Clone)]
+pub struct IoiScoreboard {
+    scoring: TaskScoring,
+    tie_break: TieBreak,
+    tasks: HashMap<(u64, u64), IoiTask>,
+}
+
+impl IoiScoreboard {
+    pub fn new(scoring: TaskScoring, tie_break: TieBreak) -> Self {
+        Self {
+            scoring,
+            tie_break,
+            tasks: HashMap::new(),
+        }
+    }
+
+    /// Submissions without a score get full points if accepted and none otherwise.
+    pub fn apply(&mut self, state: State) {
+        let subtasks = match (state.score, &state.result) {
+            (Some(score), _) => score,
+            (None, StateResult::Correct) => vec![FULL_SCORE],
+            (None, StateResult::Incorrect) => vec![0],
+            _ => return,
+        };
+
+        let task = self.tasks.entry((state.participant, state.task)).or_default();
+        task.submissions += 1;
+
+        let score = match self.scoring {
+            TaskScoring::Max => task.score.max(subtasks.iter().sum()),
+            TaskScoring::Subtasks => {
+                if task.subtasks.len() < subtasks.len() {
+                    task.subtasks.resize(subtasks.len(), 0);
+                }
+                task.subtasks.iter_mut().zip(&subtasks).for_each(|(best, &v)| *best = v.max(*best));
+                task.subtasks.iter().sum()
+            },
+        };
+
+        if score > task.score {
+            task.score = score;
+            task.last_improvement = state.time;
+        }
+    }
+
+    pub fn task(&self, participant: u64, task: u64) -> Option<&IoiTask> {
+        self.tasks.get(&(participant, task))
+    }
+
+    fn cmp_totals(&self, l: &IoiTotal, r: &IoiTotal) -> Ordering {
+        let tie = match self.tie_break {
+            TieBreak::None => Ordering::Equal,
+            TieBreak::LastImprovement => r.last_improvement.cmp(&l.last_improvement),
+            TieBreak::Submissions => r.submissions.cmp(&l.submissions),
+        };
+        l.score.cmp(&r.score).then(tie)
+    }
+
+    /// Ranking with one-based ranks, participants equal after the tie-break share the rank.
+    pub fn ranks(&self) -> Vec<(usize, IoiTotal)> {
+        let mut totals: HashMap<u64, IoiTotal> = HashMap::new();
+        self.tasks.iter().for_each(|(&(participant, _), task)| {
+            let total = totals.entry(participant).or_insert(IoiTotal {
+                participant,
+                score: 0,
+                submissions: 0,
+                last_improvement: 0,
+            });
+            total.score += task.score;
+            total.submissions += task.submissions;
+            if task.score > 0 {
+                total.last_improvement = total.last_improvement.max(task.last_improvement);
+            }
+        });
+
+        let mut ranking = totals.into_values().collect::<Vec<_>>();
+        ranking.sort_by(|l, r| self.cmp_totals(l, r).reverse().then(l.participant.cmp(&r.participant)));
+
+        let mut res: Vec<(usize, IoiTotal)> = Vec::with_capacity(ranking.len());
+        ranking.into_iter().enumerate().for_each(|(idx, total)| {
+            let rank = match res.last() {
+                Some((rank, last)) if self.cmp_totals(last, &total).is_eq() => *rank,
+                _ => idx + 1,
+            };
+            res.push((rank, total));
+        });
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{resolve_rejudges, IoiScoreboard, State, TaskScoring, TieBreak};
+
+    fn scoreboard(scoring: TaskScoring, tie_break: TieBreak) -> IoiScoreboard {
+        let mut scoreboard = IoiScoreboard::new(scoring, tie_break);
+        [
+            "1 1 10 I score=20+0+10",
+            "1 1 20 I score=0+30+0",
+            "2 1 15 I score=20+30+0",
+            "2 2 50 C",
+            "1 2 40 C score=100",
+            "3 1 5 I",
+        ]
+        .into_iter()
+        .for_each(|v| scoreboard.apply(State::try_from(v).unwrap()));
+        scoreboard
+    }
+
+    #[test]
+    fn test_ioi_scoring() {
+        let ranks = |scoreboard: IoiScoreboard| scoreboard
+            .ranks()
+            .into_iter()
+            .map(|(rank, v)| (rank, v.participant, v.score))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            ranks(scoreboard(TaskScoring::Max, TieBreak::None)),
+            vec![(1, 2, 150), (2, 1, 130), (3, 3, 0)],
+        );
+        assert_eq!(
+            ranks(scoreboard(TaskScoring::Subtasks, TieBreak::None)),
+            vec![(1, 1, 160), (2, 2, 150), (3, 3, 0)],
+        );
+    }
+
+    #[test]
+    fn test_ioi_tie_break() {
+        let mut scoreboard = IoiScoreboard::new(TaskScoring::Max, TieBreak::LastImprovement);
+        ["2 1 30 C", "1 1 40 I", "1 1 50 C"]
+            .into_iter()
+            .for_each(|v| scoreboard.apply(State::try_from(v).unwrap()));
+
+        assert_eq!(
+            scoreboard.ranks().into_iter().map(|(rank, v)| (rank, v.participant)).collect::<Vec<_>>(),
+            vec![(1, 2), (2, 1)],
+        );
+        assert_eq!(scoreboard.task(1, 1).map(|v| v.submissions), Some(2));
+    }
+
+    #[test]
+    fn test_ioi_rejudge_score() {
+        let mut scoreboard = IoiScoreboard::new(TaskScoring::Max, TieBreak::None);
+        let states = ["1 1 10 I id=1 score=10", "1 1 30 I id=1 score=90"].map(|v| State::try_from(v).unwrap());
+        resolve_rejudges(states).into_iter().for_each(|v| scoreboard.apply(v));
+
+        assert_eq!(
+            scoreboard.ranks().into_iter().map(|(rank, v)| (rank, v.participant, v.score)).collect::<Vec<_>>(),
+            vec![(1, 1, 90)],
+        );
+        assert_eq!(scoreboard.task(1, 1).map(|v| v.submissions), Some(1));
+    }
+
+    #[test]
+    fn test_ioi_rejudge_verdict_only() {
+        let mut scoreboard = IoiScoreboard::new(TaskScoring::Max, TieBreak::None);
+        let states = ["1 1 10 U id=1 score=60", "1 1 30 I id=1"].map(|v| State::try_from(v).unwrap());
+        resolve_rejudges(states).into_iter().for_each(|v| scoreboard.apply(v));
+
+        assert_eq!(
+            scoreboard.ranks().into_iter().map(|(rank, v)| (rank, v.participant, v.score)).collect::<Vec<_>>(),
+            vec![(1, 1, 60)],
+        );
+    }
+}
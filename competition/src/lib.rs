@@ -0,0 +1,41 @@
+mod clics;
+mod clock;
+mod follow;
+mod format;
+mod history;
+mod ioi;
+mod medals;
+mod parser;
+mod registry;
+mod rules;
+mod scoreboard;
+mod season;
+#[cfg(feature = "serve")]
+mod serve;
+mod standings;
+mod state;
+mod stats;
+mod task;
+mod total;
+mod validate;
+
+pub use clics::{parse_contest_time, scoreboard_json, ClicsIds, EventFeed};
+pub use clock::{Clock, Rounding};
+pub use follow::Follower;
+pub use format::Format;
+pub use history::{History, Snapshot};
+pub use ioi::{IoiScoreboard, IoiTask, IoiTotal, TaskScoring, TieBreak};
+pub use medals::{Medal, Medals};
+pub use parser::{Block, Blocks, ParseError, States};
+pub use registry::{Registry, Team};
+pub use rules::ScoringRules;
+pub use scoreboard::{RankChange, Rejudge, Scoreboard};
+pub use season::{Aggregation, ContestResult, Season, SeasonEntry};
+#[cfg(feature = "serve")]
+pub use serve::{ingest, Server, SharedScoreboard};
+pub use standings::Standings;
+pub use state::{resolve_rejudges, State, StateResult};
+pub use stats::{Statistics, TaskStats};
+pub use task::TaskState;
+pub use total::{shared_ranks, Total};
+pub use validate::{parse_ids, parse_range, ContestConfig, ValidationError, Validator};
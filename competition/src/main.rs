@@ -1,232 +1,425 @@
-use std::{convert::TryFrom, hash::Hash, collections::HashSet, fmt::Display};
-
-#[derive(Debug, PartialEq, Eq)]
-pub enum StateResult {
-    Correct,
-    Incorrect,
-    ClarificationRequest,
-    Unjudged,
-    ErroneousSubmission,
+use std::{fs::{File, OpenOptions}, io::{BufRead, BufReader, Write}, time::Duration};
+
+use competition::{
+    parse_ids, parse_range, resolve_rejudges, Aggregation, Blocks, Clock, ContestConfig, EventFeed, Follower, Format,
+    History, IoiScoreboard, Medals, Registry, Rounding, Scoreboard, ScoringRules, Season, Standings, State, States, Statistics, TaskScoring, TieBreak,
+    Validator,
+};
+
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(500);
+
+fn convert_io_error(err: std::io::Error) -> String {
+    format!("Got an io error: \"{}\"", err)
 }
 
-impl TryFrom<&str> for StateResult {
-    type Error = String;
+fn read_blocks(options: &Options, reader: impl BufRead) -> Result<Vec<Vec<State>>, String> {
+    let mut blocks = Blocks::new(reader);
+    blocks.set_clock(options.clock.clone());
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
-            "C" => Ok(StateResult::Correct),
-            "I" => Ok(StateResult::Incorrect),
-            "R" => Ok(StateResult::ClarificationRequest),
-            "U" => Ok(StateResult::Unjudged),
-            "E" => Ok(StateResult::ErroneousSubmission),
-            _ => Err(format!("Unknown game result \"{}\".", value)),
-        }
-    }
+    blocks
+        .map(|block| {
+            let block = block.map_err(|e| format!("Got an error on {}", e))?;
+            let mut validator = Validator::new(&options.contest, block.number);
+
+            block.states
+                .iter()
+                .try_for_each(|(line, state)| options.validate(&mut validator, *line, state))?;
+
+            Ok(block.into_states())
+        })
+        .collect()
 }
 
-#[derive(Debug, PartialEq, Eq)]
-pub struct State {
-    pub participant: u64,
-    pub task: u64,
-    pub time: u64,
-    pub result: StateResult,
+#[derive(Debug, Default)]
+struct Options {
+    rules: ScoringRules,
+    freeze: Option<u64>,
+    reveal: bool,
+    format: Format,
+    follow: Option<String>,
+    output: Option<String>,
+    changes_only: bool,
+    registry: Option<Registry>,
+    medals: Option<Medals>,
+    contest: ContestConfig,
+    lenient: bool,
+    event_feed: Option<EventFeed>,
+    history: bool,
+    at: Option<u64>,
+    stats: bool,
+    audit: bool,
+    serve: Option<u16>,
+    season: Option<Aggregation>,
+    best: Option<usize>,
+    contests: Vec<String>,
+    ioi: Option<TaskScoring>,
+    tie_break: TieBreak,
+    start: Option<String>,
+    rounding: Rounding,
+    clock: Option<Clock>,
 }
 
-impl TryFrom<&str> for State {
-    type Error = String;
+impl Options {
+    fn from_args() -> Result<Self, String> {
+        let mut args = std::env::args().skip(1);
+        let mut options = Options::default();
+
+        while let Some(arg) = args.next() {
+            let mut value = || args
+                .next()
+                .ok_or_else(|| format!("Expected value after \"{}\".", arg));
+
+            match arg.as_str() {
+                "--rules" => options.rules = value()?.as_str().try_into()?,
+                "--freeze" => options.freeze = Some(value()?
+                    .parse()
+                    .map_err(|_| "Expected freeze time as number".to_string())?),
+                "--reveal" => options.reveal = true,
+                "--format" => options.format = value()?.as_str().try_into()?,
+                "--follow" => options.follow = Some(value()?),
+                "--output" => options.output = Some(value()?),
+                "--changes-only" => options.changes_only = true,
+                "--registry" => options.registry = Some(std::fs::read_to_string(value()?)
+                    .map_err(convert_io_error)?
+                    .as_str()
+                    .try_into()?),
+                "--medals" => options.medals = Some(value()?.as_str().try_into()?),
+                "--length" => options.contest.length = Some(value()?
+                    .parse()
+                    .map_err(|_| "Expected contest length as number".to_string())?),
+                "--tasks" => options.contest.tasks = Some(parse_ids(&value()?)?),
+                "--participants" => options.contest.participants = Some(parse_range(&value()?)?),
+                "--lenient" => options.lenient = true,
+                "--history" => options.history = true,
+                "--stats" => options.stats = true,
+                "--audit" => options.audit = true,
+                "--season" => options.season = Some(value()?.as_str().try_into()?),
+                "--best" => options.best = Some(value()?
+                    .parse()
+                    .map_err(|_| "Expected number of best contests".to_string())?),
+                "--contest" => options.contests.push(value()?),
+                "--ioi" => options.ioi = Some(value()?.as_str().try_into()?),
+                "--tie-break" => options.tie_break = value()?.as_str().try_into()?,
+                "--start" => options.start = Some(value()?),
+                "--rounding" => options.rounding = value()?.as_str().try_into()?,
+                "--serve" => options.serve = Some(value()?
+                    .parse()
+                    .map_err(|_| "Expected port as number".to_string())?),
+                "--at" => options.at = Some(value()?
+                    .parse()
+                    .map_err(|_| "Expected time as number".to_string())?),
+                "--event-feed" => options.event_feed = Some(std::fs::read_to_string(value()?)
+                    .map_err(convert_io_error)?
+                    .as_str()
+                    .try_into()?),
+                arg => return Err(format!("Unknown argument \"{}\".", arg)),
+            }
+        }
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let values = value.split_whitespace().collect::<Vec<_>>();
+        if options.reveal && options.freeze.is_none() {
+            return Err("Expected \"--freeze\" to be set for \"--reveal\".".into());
+        }
 
-        if values.len() != 4 {
-            return Err("Expected 4 arguments.".into());
+        if options.follow.is_none() && options.changes_only {
+            return Err("Expected \"--follow\" to be set for \"--changes-only\".".into());
         }
 
-        Ok(Self {
-            participant: values[0].parse().map_err(|_| "Expected participant id as number".to_string())?,
-            task: values[1].parse().map_err(|_| "Expected task id as number".to_string())?,
-            time: values[2].parse().map_err(|_| "Expected time as number".to_string())?,
-            result: values[3].try_into()?,
-        })
-    }
-}
+        if (options.history || options.at.is_some()) && (options.freeze.is_some() || options.follow.is_some()) {
+            return Err("Expected no \"--freeze\" and \"--follow\" for \"--history\" and \"--at\".".into());
+        }
 
-const TIME_FOR_INCORRECT: u64 = 20;
+        // Followed and served logs are applied as they come, without validation.
+        let validates = options.contest != ContestConfig::default() || options.lenient;
+        if validates && (options.follow.is_some() || options.serve.is_some()) {
+            return Err("Expected no \"--length\", \"--tasks\", \"--participants\" and \"--lenient\" for \"--follow\" and \"--serve\".".into());
+        }
 
-#[derive(Debug, Eq, Clone)]
-pub struct Total {
-    pub participant: u64,
-    pub solves: u64,
-    pub time: u64,
-}
+        if options.best.is_some() && options.season.is_none() {
+            return Err("Expected \"--season\" to be set for \"--best\".".into());
+        }
 
-impl Total {
-    pub fn new(participant: u64) -> Self {
-        Self {
-            participant,
-            solves: 0,
-            time: 0,
+        if options.ioi.is_some() && (options.freeze.is_some() || options.follow.is_some() || options.history || options.stats) {
+            return Err("Expected no \"--freeze\", \"--follow\", \"--history\" and \"--stats\" for \"--ioi\".".into());
         }
-    }
 
-    pub fn add_solve(&mut self) {
-        self.solves += 1
-    }
+        if options.ioi.is_none() && options.tie_break != TieBreak::None {
+            return Err("Expected \"--ioi\" to be set for \"--tie-break\".".into());
+        }
+
+        if options.start.is_none() && options.rounding != Rounding::Minutes {
+            return Err("Expected \"--start\" to be set for \"--rounding\".".into());
+        }
 
-    pub fn add_time(&mut self, time: u64) {
-        self.time += time;
+        if options.start.is_some() && (options.event_feed.is_some() || options.serve.is_some()) {
+            return Err("Expected no \"--event-feed\" and \"--serve\" for \"--start\".".into());
+        }
+
+        // CLICS scoreboards count times in minutes.
+        if options.rounding == Rounding::Seconds && options.format == Format::Json {
+            return Err("Expected no \"--format json\" for \"--rounding seconds\".".into());
+        }
+
+        if let Some(start) = &options.start {
+            let mut clock = Clock::new(start, options.rounding)?;
+            clock.set_length(options.contest.length);
+            options.clock = Some(clock);
+
+            // Every time given in minutes is scaled to the units of contest time.
+            let per_minute = options.rounding.per_minute();
+            options.rules = options.rules.scaled(per_minute);
+            options.contest.length = options.contest.length.map(|v| v * per_minute);
+            options.freeze = options.freeze.map(|v| v * per_minute);
+            options.at = options.at.map(|v| v * per_minute);
+        }
+
+        if options.registry.is_none() {
+            options.registry = options.event_feed.as_ref().map(|v| v.registry.clone());
+        }
+
+        Ok(options)
     }
-}
 
-impl PartialEq for Total {
-    fn eq(&self, other: &Self) -> bool {
-        self.participant == other.participant
+    fn validate(&self, validator: &mut Validator, line: usize, state: &State) -> Result<(), String> {
+        validator
+            .check(line, state)
+            .into_iter()
+            .try_for_each(|e| match self.lenient {
+                true => {
+                    eprintln!("Warning: {}", e);
+                    Ok(())
+                },
+                false => Err(format!("Got an invalid submission in {}", e)),
+            })
     }
-} 
 
-impl Ord for Total {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.solves.cmp(&other.solves)
-            .then(self.time.cmp(&other.time).reverse())
-            .then(self.participant.cmp(&other.participant))
+    fn scoreboard(&self) -> Scoreboard {
+        match self.freeze {
+            Some(freeze) => Scoreboard::with_freeze(self.rules, freeze),
+            None => Scoreboard::new(self.rules),
+        }
     }
 }
 
-impl PartialOrd for Total {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+fn sink(options: &Options, append: bool) -> Result<Box<dyn Write>, String> {
+    match &options.output {
+        Some(output) => Ok(Box::new(OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(output)
+            .map_err(convert_io_error)?)),
+        None => Ok(Box::new(std::io::stdout())),
     }
 }
 
-impl Hash for Total {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.participant.hash(state)
-    }
+fn read_from_event_feed(options: &Options, feed: &EventFeed) -> Result<Vec<Vec<State>>, String> {
+    let mut validator = Validator::new(&options.contest, 1);
+
+    feed.states
+        .iter()
+        .zip(&feed.lines)
+        .try_for_each(|(state, &line)| options.validate(&mut validator, line, state))?;
+
+    Ok(vec![feed.states.clone()])
 }
 
-impl Display for Total {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("{} {} {}", self.participant, self.solves, self.time))
-    }
+/// Reads each file as a separate contest of plain submission lines.
+fn read_from_files(options: &Options, paths: &[String]) -> Result<Vec<Vec<State>>, String> {
+    paths
+        .iter()
+        .enumerate()
+        .map(|(i, path)| {
+            let mut validator = Validator::new(&options.contest, i + 1);
+
+            let mut states = States::new(File::open(path).map(BufReader::new).map_err(convert_io_error)?);
+            states.set_clock(options.clock.clone());
+
+            states
+                .map(|res| {
+                    let (line, state) = res.map_err(|e| format!("Got an error in {}: {}", path, e))?;
+                    options.validate(&mut validator, line, &state)?;
+                    Ok(state)
+                })
+                .collect()
+        })
+        .collect()
 }
 
-fn convert_io_error(err: std::io::Error) -> String {
-    format!("Got an io error: \"{}\"", err)
+fn print_season(options: &Options, aggregation: &Aggregation, blocks: Vec<Vec<State>>) -> Result<(), String> {
+    let mut season = Season::new(aggregation.clone(), options.best);
+
+    blocks.into_iter().for_each(|states| {
+        let mut scoreboard = Scoreboard::new(options.rules);
+        states.into_iter().for_each(|v| scoreboard.apply(v));
+        season.add_contest(&scoreboard);
+    });
+
+    write!(sink(options, false)?, "{}", season.to_text()).map_err(convert_io_error)
 }
 
-fn solve_from_stdin() -> Result<Vec<Vec<Total>>, String> {
-    let mut line = String::new();
+fn follow(options: &Options, path: &str) -> Result<(), String> {
+    let reader = File::open(path)
+        .map(BufReader::new)
+        .map_err(convert_io_error)?;
+    let sink = sink(options, true)?;
+
+    let mut follower = Follower::new(reader, sink, options.scoreboard(), options.format);
+    follower.set_changes_only(options.changes_only);
+    follower.set_registry(options.registry.clone());
+    follower.set_clock(options.clock.clone());
+    follower.run(FOLLOW_INTERVAL)
+}
 
-    let count = {
-        let _ = std::io::stdin()
-            .read_line(&mut line)
-            .map_err(convert_io_error)?;
+#[cfg(feature = "serve")]
+fn serve(options: &Options, port: u16) -> Result<(), String> {
+    use std::sync::{Arc, Mutex};
 
-        line.trim()
-            .parse::<u64>()
-            .map_err(|_| format!("Expected unsigned integer on first line, found: \"{}\".", line.trim()))?
-    };
+    use competition::{ingest, Server};
 
-    line.clear();
+    let scoreboard = Arc::new(Mutex::new(options.scoreboard()));
+    let mut server = Server::bind(port, scoreboard.clone())?;
+    server.set_registry(options.registry.clone());
 
-    std::io::stdin()
-        .read_line(&mut line)
-        .map(|_| match line.trim() {
-            clean if clean.is_empty() => {
-		line.clear();
-		Ok(())
-	    },
-            _ => Err("Expected empty line after first.".to_string())
-        })
-        .map_err(convert_io_error)??;
-
-    (1..=count)
-        .map(|i| {
-            let mut totals: HashSet<Total> = HashSet::new();
-            
-            (1..)
-                .map_while(|j| {
-                    let mut line = String::new();
-
-                    let res = std::io::stdin()
-                        .read_line(&mut line)
-                        .map_err(|e| e.to_string());
-
-                    match (res, line.trim()) {
-                        (Err(e), _) => Some(Err(e)),
-                        (_, line) if line.is_empty() => None,
-                        (_, line) => Some(line.try_into()),
-                    }
-                    .map(|v: Result<State, _>|
-			 v.map_err(|e| format!("line {}: {}", j, e)))
-                })
-                .try_for_each(|v| v.map(|state| {
-                    let default = Total::new(state.participant);
-                    let total = &mut totals.take(&default).unwrap_or(default);
-
-		    match state.result {
-                        StateResult::Correct => {
-                            total.add_solve();
-                            total.add_time(state.time);
-                        },
-                        StateResult::Incorrect => total.add_time(TIME_FOR_INCORRECT),
-                        _ => (),
-                        
-                    }
-                    totals.insert(total.to_owned());
-                }))
-                .map_err(|e| format!("Got and error in block {}: {}", i, e))?;
-
-            let mut res = totals.into_iter().collect::<Vec<_>>();
-            res.sort_unstable_by(|l, r| l.cmp(r).reverse());
-            Ok(res)
-            
-        })
-        .collect::<Result<_, _>>()
+    let follow = options.follow.clone();
+    std::thread::spawn(move || {
+        let res = match follow {
+            Some(path) => File::open(path)
+                .map_err(convert_io_error)
+                .and_then(|v| ingest(BufReader::new(v), &scoreboard, Some(FOLLOW_INTERVAL))),
+            None => ingest(std::io::stdin().lock(), &scoreboard, None),
+        };
+
+        if let Err(e) = res {
+            eprintln!("{}", e);
+        }
+    });
+
+    eprintln!("Serving scoreboard on http://{}/", server.local_addr()?);
+    server.run()
 }
 
-fn main() {
-    match solve_from_stdin() {
-        Ok(totals) => totals
-            .iter()
-            .for_each(|v| {
-                v.iter().for_each(|v| println!("{}", v));
-                println!()
-            }),
-        Err(e) => eprintln!("{}", e),
-    }
+#[cfg(not(feature = "serve"))]
+fn serve(_: &Options, _: u16) -> Result<(), String> {
+    Err("Built without \"serve\" feature.".into())
 }
 
+fn render(options: &Options, scoreboard: &Scoreboard) -> Result<String, String> {
+    let mut standings = Standings::new(scoreboard);
+    standings.set_ids(options.event_feed.as_ref().map(|v| &v.ids));
+    if let Some(medals) = &options.medals {
+        standings.set_medals(medals);
+    }
+
+    let registry = match &options.registry {
+        Some(registry) => registry,
+        None => return Ok(options.format.render_standings(&standings)),
+    };
 
-#[cfg(test)]
-mod test {
-    use std::cmp::Ordering;
+    registry.check(scoreboard)?;
+    standings.set_registry(Some(registry));
 
-    use crate::Total;
+    let mut res = options.format.render_standings(&standings);
+    if options.format == Format::Json {
+        return Ok(res);
+    }
 
-    #[test]
-    fn test_total_compare() {
-        let a = Total {
-            participant: 1,
-            solves: 5,
-            time: 666,
-        };
-        let b = Total {
-            participant: 2,
-            solves: 3,
-            time: 1,
-        };
+    registry.divisions().into_iter().for_each(|division| {
+        let mut standings = Standings::with_ranking(scoreboard, registry.division_ranking(scoreboard, division));
+        standings.set_registry(Some(registry));
+
+        res.push_str(&format!("\nDivision {}:\n", division));
+        res.push_str(&options.format.render_standings(&standings));
+    });
 
-        assert_eq!(a.cmp(&b), Ordering::Greater);
+    Ok(res)
+}
 
-        let b = Total {
-            participant: 2,
-            solves: 5,
-            time: 1000,
+fn print(options: &Options, blocks: Vec<Vec<State>>) -> Result<(), String> {
+    let mut sink = sink(options, false)?;
+
+    blocks.into_iter().try_for_each(|states| {
+        let output = match (options.history, options.at) {
+            _ if options.stats => {
+                let stats = resolve_rejudges(states).iter().collect::<Statistics>();
+                match options.format {
+                    Format::Json => format!("{:#}", stats.to_json()),
+                    _ => stats.to_text(),
+                }
+            },
+            _ if options.ioi.is_some() => {
+                let mut scoreboard = IoiScoreboard::new(options.ioi.unwrap_or_default(), options.tie_break);
+                resolve_rejudges(states).into_iter().for_each(|v| scoreboard.apply(v));
+                scoreboard
+                    .ranks()
+                    .into_iter()
+                    .map(|(rank, v)| format!("{} {}\n", rank, v))
+                    .collect()
+            },
+            (true, _) => History::new(options.rules, states).to_csv(),
+            (_, Some(time)) => render(options, &History::new(options.rules, states).scoreboard_at(time))?,
+            _ => {
+                let mut scoreboard = options.scoreboard();
+                states.into_iter().for_each(|v| scoreboard.apply(v));
+                let mut output = render(options, &scoreboard)?;
+
+                if options.audit {
+                    scoreboard.rejudges().iter().for_each(|v| {
+                        output.push_str(&format!("\n{}", v));
+                        v.changes.iter().for_each(|v| output.push_str(&format!("\n  {}", v)));
+                    });
+                    output.push('\n');
+                }
+
+                if options.reveal {
+                    scoreboard.unfreeze().iter().for_each(|v| output.push_str(&format!("\n{}", v)));
+                    output.push_str("\n\n");
+                    output.push_str(&render(options, &scoreboard)?);
+                }
+
+                output
+            },
         };
 
-        assert_eq!(a.cmp(&b), Ordering::Greater);
+        writeln!(sink, "{}\n", output.trim_end_matches('\n')).map_err(convert_io_error)
+    })
+}
+
+fn main() {
+    let options = match Options::from_args() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        },
+    };
+
+    if let Some(port) = options.serve {
+        if let Err(e) = serve(&options, port) {
+            eprintln!("{}", e);
+        }
+        return;
+    }
+
+    if let Some(path) = &options.follow {
+        if let Err(e) = follow(&options, path) {
+            eprintln!("{}", e);
+        }
+        return;
+    }
+
+    let blocks = match &options.event_feed {
+        Some(feed) => read_from_event_feed(&options, feed),
+        None if !options.contests.is_empty() => read_from_files(&options, &options.contests),
+        None => read_blocks(&options, std::io::stdin().lock()),
+    };
+
+    let res = blocks.and_then(|v| match &options.season {
+        Some(aggregation) => print_season(&options, aggregation, v),
+        None => print(&options, v),
+    });
 
-        assert_eq!(a.cmp(&a), Ordering::Equal);
+    if let Err(e) = res {
+        eprintln!("{}", e);
     }
 }
@@ -0,0 +1,100 @@
+use std::fmt::Display;
+
+use crate::Total;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Medal {
+    Gold,
+    Silver,
+    Bronze,
+    HonourableMention,
+}
+
+impl Display for Medal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Medal::Gold => "gold",
+            Medal::Silver => "silver",
+            Medal::Bronze => "bronze",
+            Medal::HonourableMention => "honourable",
+        })
+    }
+}
+
+/// Number of places awarded with each medal, parsed from "gold,silver,bronze[,honourable]".
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Medals {
+    pub gold: usize,
+    pub silver: usize,
+    pub bronze: usize,
+    pub honourable: usize,
+}
+
+impl TryFrom<&str> for Medals {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let values = value
+            .split(',')
+            .map(|v| v.trim().parse::<usize>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| format!("Expected medal counts as numbers, found: \"{}\".", value))?;
+
+        match values[..] {
+            [gold, silver, bronze] => Ok(Self { gold, silver, bronze, honourable: 0 }),
+            [gold, silver, bronze, honourable] => Ok(Self { gold, silver, bronze, honourable }),
+            _ => Err(format!("Expected 3 or 4 medal counts, found: {}.", values.len())),
+        }
+    }
+}
+
+impl Medals {
+    /// Awards medals by shared rank, so ties on a boundary get the better medal.
+    /// Participants without solves are never awarded.
+    pub fn award(&self, ranking: &[Total], ranks: &[usize]) -> Vec<Option<Medal>> {
+        let silver = self.gold + self.silver;
+        let bronze = silver + self.bronze;
+        let honourable = bronze + self.honourable;
+
+        ranking
+            .iter()
+            .zip(ranks)
+            .map(|(total, &rank)| match rank {
+                _ if total.solves == 0 => None,
+                rank if rank <= self.gold => Some(Medal::Gold),
+                rank if rank <= silver => Some(Medal::Silver),
+                rank if rank <= bronze => Some(Medal::Bronze),
+                rank if rank <= honourable => Some(Medal::HonourableMention),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{shared_ranks, Medal, Medals, Total};
+
+    #[test]
+    fn test_award_medals() {
+        let ranking = [(1, 3, 100), (2, 2, 50), (3, 2, 50), (4, 1, 10), (5, 1, 20), (6, 0, 0)]
+            .into_iter()
+            .map(|(participant, solves, time)| Total {
+                participant,
+                solves,
+                time,
+            })
+            .collect::<Vec<_>>();
+        let medals = Medals::try_from("1,1,1,5").unwrap();
+
+        assert_eq!(medals.award(&ranking, &shared_ranks(&ranking)), vec![
+            Some(Medal::Gold),
+            Some(Medal::Silver),
+            Some(Medal::Silver),
+            Some(Medal::HonourableMention),
+            Some(Medal::HonourableMention),
+            None,
+        ]);
+        assert!(Medals::try_from("1,2").is_err());
+    }
+}
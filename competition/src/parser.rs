@@ -0,0 +1,240 @@
+use std::{fmt::Display, io::BufRead};
+
+use crate::{Clock, State};
+
+/// Parse or io error together with the one-based line it happened on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("line {}: {}", self.line, self.message))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Submissions of a single block with the lines they were read from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Block {
+    /// One-based number of the block.
+    pub number: usize,
+    pub states: Vec<(usize, State)>,
+}
+
+impl Block {
+    pub fn into_states(self) -> Vec<State> {
+        self.states.into_iter().map(|(_, v)| v).collect()
+    }
+}
+
+struct LineReader<R> {
+    reader: R,
+    line: usize,
+    clock: Option<Clock>,
+}
+
+impl<R: BufRead> LineReader<R> {
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line: self.line,
+            message: message.into(),
+        }
+    }
+
+    /// Next line without line ending and surrounding whitespace, `None` at the end.
+    fn next_line(&mut self) -> Result<Option<String>, ParseError> {
+        let mut line = String::new();
+        self.line += 1;
+
+        match self.reader.read_line(&mut line) {
+            Ok(0) => Ok(None),
+            Ok(_) => Ok(Some(line.trim().to_string())),
+            Err(e) => Err(self.error(format!("Got an io error: \"{}\"", e))),
+        }
+    }
+
+    fn parse(&self, line: &str) -> Result<(usize, State), ParseError> {
+        let state = match &self.clock {
+            Some(clock) => State::parse_with(line, |v| clock.time(v)),
+            None => State::try_from(line),
+        };
+
+        state
+            .map(|v| (self.line, v))
+            .map_err(|e| self.error(e))
+    }
+}
+
+/// Lazily reads "count, empty line, blocks separated by empty lines" from any reader.
+pub struct Blocks<R> {
+    lines: LineReader<R>,
+    count: Option<usize>,
+    number: usize,
+}
+
+impl<R: BufRead> Blocks<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: LineReader { reader, line: 0, clock: None },
+            count: None,
+            number: 0,
+        }
+    }
+
+    /// Convert submission timestamps to contest time with `clock`.
+    pub fn set_clock(&mut self, clock: Option<Clock>) {
+        self.lines.clock = clock;
+    }
+
+    fn read_header(&mut self) -> Result<usize, ParseError> {
+        let line = self.lines.next_line()?.unwrap_or_default();
+        let count = line
+            .parse()
+            .map_err(|_| self.lines.error(format!("Expected unsigned integer on first line, found: \"{}\".", line)))?;
+
+        match self.lines.next_line()? {
+            Some(line) if !line.is_empty() => Err(self.lines.error("Expected empty line after first.")),
+            _ => Ok(count),
+        }
+    }
+
+    fn read_block(&mut self) -> Result<Block, ParseError> {
+        let mut block = Block {
+            number: self.number,
+            states: vec![],
+        };
+
+        while let Some(line) = self.lines.next_line()? {
+            if line.is_empty() {
+                break;
+            }
+            block.states.push(self.lines.parse(&line)?);
+        }
+
+        Ok(block)
+    }
+}
+
+impl<R: BufRead> Iterator for Blocks<R> {
+    type Item = Result<Block, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let count = match self.count {
+            Some(count) => count,
+            None => match self.read_header() {
+                Ok(count) => *self.count.insert(count),
+                Err(e) => {
+                    self.count = Some(0);
+                    return Some(Err(e));
+                },
+            },
+        };
+
+        if self.number >= count {
+            return None;
+        }
+        self.number += 1;
+
+        let res = self.read_block();
+        if res.is_err() {
+            // Nothing sensible can be read after a broken line.
+            self.count = Some(0);
+        }
+        Some(res)
+    }
+}
+
+/// Lazily reads a plain submission log, skipping empty lines.
+pub struct States<R> {
+    lines: LineReader<R>,
+}
+
+impl<R: BufRead> States<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: LineReader { reader, line: 0, clock: None },
+        }
+    }
+
+    /// Convert submission timestamps to contest time with `clock`.
+    pub fn set_clock(&mut self, clock: Option<Clock>) {
+        self.lines.clock = clock;
+    }
+}
+
+impl<R: BufRead> Iterator for States<R> {
+    type Item = Result<(usize, State), ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.lines.next_line() {
+                Ok(Some(line)) if line.is_empty() => continue,
+                Ok(Some(line)) => return Some(self.lines.parse(&line)),
+                Ok(None) => return None,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Blocks, Clock, ParseError, Rounding, State, StateResult, States};
+
+    #[test]
+    fn test_parse_blocks() {
+        let input = "2 \r\n\r\n1 1 10 C\r\n2 1 20 I  \r\n\r\n3 2 30 C";
+        let blocks = Blocks::new(input.as_bytes()).collect::<Result<Vec<_>, _>>().unwrap();
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].states, vec![
+            (3, State::new(1, 1, 10, StateResult::Correct)),
+            (4, State::new(2, 1, 20, StateResult::Incorrect)),
+        ]);
+        assert_eq!(blocks[1].number, 2);
+        assert_eq!(blocks[1].states, vec![(6, State::new(3, 2, 30, StateResult::Correct))]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let mut blocks = Blocks::new("2\n\n1 1 10 C\n\n1 1 x C\n".as_bytes());
+
+        assert!(blocks.next().unwrap().is_ok());
+        assert_eq!(blocks.next(), Some(Err(ParseError {
+            line: 5,
+            message: "Expected time as number".into(),
+        })));
+        assert_eq!(blocks.next(), None);
+
+        assert_eq!(
+            Blocks::new("1\n1 1 10 C\n".as_bytes()).next().unwrap().unwrap_err().to_string(),
+            "line 2: Expected empty line after first.",
+        );
+    }
+
+    #[test]
+    fn test_parse_states() {
+        let states = States::new("1 1 10 C\r\n\n2 1 20 I\n".as_bytes()).collect::<Result<Vec<_>, _>>().unwrap();
+
+        assert_eq!(states.iter().map(|(line, _)| *line).collect::<Vec<_>>(), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_parse_timestamps() {
+        let mut clock = Clock::new("10:00:00", Rounding::Minutes).unwrap();
+        clock.set_length(Some(60));
+
+        let mut states = States::new("1 1 10:05:30 C\n2 1 11:00:01 I\n".as_bytes());
+        states.set_clock(Some(clock));
+
+        assert_eq!(states.next(), Some(Ok((1, State::new(1, 1, 5, StateResult::Correct)))));
+        assert_eq!(
+            states.next().unwrap().unwrap_err().to_string(),
+            "line 2: Submission at \"11:00:01\" is after the end of contest.",
+        );
+    }
+}
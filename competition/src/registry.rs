@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+
+use crate::{Scoreboard, Total};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Team {
+    pub id: u64,
+    pub name: String,
+    pub institution: String,
+    pub division: String,
+}
+
+impl TryFrom<&str> for Team {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let values = value.split('\t').map(str::trim).collect::<Vec<_>>();
+
+        if values.len() != 4 {
+            return Err("Expected 4 tab separated fields: id, name, institution and division.".into());
+        }
+
+        Ok(Self {
+            id: values[0].parse().map_err(|_| "Expected team id as number".to_string())?,
+            name: values[1].into(),
+            institution: values[2].into(),
+            division: values[3].into(),
+        })
+    }
+}
+
+/// Known teams, loaded from lines of tab separated "id name institution division".
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Registry {
+    teams: BTreeMap<u64, Team>,
+}
+
+impl TryFrom<&str> for Registry {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut teams = BTreeMap::new();
+
+        value
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .try_for_each(|(idx, line)| {
+                let team = Team::try_from(line)
+                    .map_err(|e| format!("Got an error in registry on line {}: {}", idx + 1, e))?;
+
+                match teams.insert(team.id, team) {
+                    Some(team) => Err(format!("Duplicate team id {} in registry on line {}.", team.id, idx + 1)),
+                    None => Ok(()),
+                }
+            })?;
+
+        Ok(Self { teams })
+    }
+}
+
+impl FromIterator<Team> for Registry {
+    fn from_iter<T: IntoIterator<Item = Team>>(iter: T) -> Self {
+        Self {
+            teams: iter.into_iter().map(|v| (v.id, v)).collect(),
+        }
+    }
+}
+
+impl Registry {
+    pub fn get(&self, id: u64) -> Option<&Team> {
+        self.teams.get(&id)
+    }
+
+    /// Display name of the participant, falling back to its id.
+    pub fn name(&self, id: u64) -> String {
+        self.get(id).map_or_else(|| id.to_string(), |v| v.name.clone())
+    }
+
+    /// Sorted names of all non-empty divisions.
+    pub fn divisions(&self) -> Vec<&str> {
+        let mut res = self.teams
+            .values()
+            .map(|v| v.division.as_str())
+            .filter(|v| !v.is_empty())
+            .collect::<Vec<_>>();
+        res.sort_unstable();
+        res.dedup();
+        res
+    }
+
+    /// Fails with all participant ids of the scoreboard that are missing from the registry.
+    pub fn check(&self, scoreboard: &Scoreboard) -> Result<(), String> {
+        let mut unknown = scoreboard
+            .totals()
+            .map(|v| v.participant)
+            .filter(|v| !self.teams.contains_key(v))
+            .collect::<Vec<_>>();
+
+        if unknown.is_empty() {
+            return Ok(());
+        }
+
+        unknown.sort_unstable();
+        Err(format!(
+            "Unknown participants: {}.",
+            unknown.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", "),
+        ))
+    }
+
+    /// Ranking of the teams of a single division.
+    pub fn division_ranking(&self, scoreboard: &Scoreboard, division: &str) -> Vec<Total> {
+        scoreboard
+            .ranking()
+            .into_iter()
+            .filter(|v| self.get(v.participant).is_some_and(|v| v.division == division))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Registry, Scoreboard, ScoringRules, State};
+
+    #[test]
+    fn test_registry_divisions() {
+        let registry = Registry::try_from(concat!(
+            "1\tAlpha\tOmSTU\tstudents\n",
+            "\n",
+            "2\tBeta\tOmSU\tschool\n",
+            "3\tGamma\tOmSTU\tstudents\n",
+        ))
+        .unwrap();
+
+        let mut scoreboard = Scoreboard::new(ScoringRules::Classic);
+        ["1 1 10 C", "2 1 20 C", "3 1 30 C", "4 1 40 C"]
+            .into_iter()
+            .for_each(|v| scoreboard.apply(State::try_from(v).unwrap()));
+
+        assert_eq!(registry.divisions(), vec!["school", "students"]);
+        assert_eq!(
+            registry.division_ranking(&scoreboard, "students").iter().map(|v| v.participant).collect::<Vec<_>>(),
+            vec![1, 3],
+        );
+        assert_eq!(registry.name(2), "Beta");
+        assert_eq!(registry.check(&scoreboard), Err("Unknown participants: 4.".into()));
+    }
+
+    #[test]
+    fn test_registry_errors() {
+        assert_eq!(
+            Registry::try_from("1\tAlpha\tOmSTU\n"),
+            Err("Got an error in registry on line 1: Expected 4 tab separated fields: id, name, institution and division.".into()),
+        );
+        assert_eq!(
+            Registry::try_from("1\tAlpha\tOmSTU\ta\n1\tBeta\tOmSU\tb\n"),
+            Err("Duplicate team id 1 in registry on line 2.".into()),
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use std::convert::TryFrom;
+
+const TIME_FOR_INCORRECT: u64 = 20;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ScoringRules {
+    /// ICPC rules: penalty is charged only for tasks that are eventually accepted.
+    #[default]
+    Classic,
+    /// Penalty of the given amount is charged for every incorrect submission, even on unsolved tasks.
+    PenaltyAlways(u64),
+    /// ICPC rules with a custom amount of penalty minutes.
+    Custom(u64),
+}
+
+impl ScoringRules {
+    pub fn penalty(&self) -> u64 {
+        match self {
+            ScoringRules::Classic => TIME_FOR_INCORRECT,
+            ScoringRules::PenaltyAlways(penalty) | ScoringRules::Custom(penalty) => *penalty,
+        }
+    }
+
+    pub fn charges_unsolved(&self) -> bool {
+        matches!(self, ScoringRules::PenaltyAlways(_))
+    }
+
+    /// Same rules with the penalty counted in units `per_minute` times smaller than a minute.
+    pub fn scaled(self, per_minute: u64) -> Self {
+        match self {
+            _ if per_minute == 1 => self,
+            ScoringRules::Classic => ScoringRules::Custom(TIME_FOR_INCORRECT * per_minute),
+            ScoringRules::PenaltyAlways(penalty) => ScoringRules::PenaltyAlways(penalty * per_minute),
+            ScoringRules::Custom(penalty) => ScoringRules::Custom(penalty * per_minute),
+        }
+    }
+}
+
+impl TryFrom<&str> for ScoringRules {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "classic" => Ok(ScoringRules::Classic),
+            "always" => Ok(ScoringRules::PenaltyAlways(TIME_FOR_INCORRECT)),
+            value => value
+                .parse()
+                .map(ScoringRules::Custom)
+                .map_err(|_| format!("Unknown scoring rules \"{}\".", value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ScoringRules;
+
+    #[test]
+    fn test_scaled_rules() {
+        assert_eq!(ScoringRules::Classic.scaled(60).penalty(), 1200);
+        assert_eq!(ScoringRules::Custom(5).scaled(60), ScoringRules::Custom(300));
+        assert_eq!(ScoringRules::try_from("always").map(|v| v.scaled(60)), Ok(ScoringRules::PenaltyAlways(1200)));
+        assert_eq!(ScoringRules::PenaltyAlways(20).scaled(1), ScoringRules::PenaltyAlways(20));
+    }
+}
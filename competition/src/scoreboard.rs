@@ -0,0 +1,352 @@
+use std::{collections::{HashMap, HashSet}, fmt::Display};
+
+use crate::{shared_ranks, ScoringRules, State, StateResult, TaskState, Total};
+
+/// Rank change of a participant, caused by a reveal or a rejudge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RankChange {
+    pub participant: u64,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl Display for RankChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{} {} -> {}", self.participant, self.from, self.to))
+    }
+}
+
+/// Verdict change of a submission and the rank changes it caused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rejudge {
+    pub id: u64,
+    pub participant: u64,
+    pub task: u64,
+    pub from: StateResult,
+    pub to: StateResult,
+    pub changes: Vec<RankChange>,
+}
+
+impl Display for Rejudge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "rejudge {} of {} on {}: {} -> {}",
+            self.id, self.participant, self.task, self.from, self.to,
+        ))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Submission {
+    state: State,
+    /// Verdict is hidden by the freeze.
+    hidden: bool,
+}
+
+/// Standings of a single contest, updated one submission at a time.
+#[derive(Debug, Default, Clone)]
+pub struct Scoreboard {
+    rules: ScoringRules,
+    tasks: HashMap<(u64, u64), TaskState>,
+    totals: HashMap<u64, Total>,
+    last_time: u64,
+    freeze: Option<u64>,
+    revealed: HashSet<u64>,
+    submissions: HashMap<(u64, u64), Vec<Submission>>,
+    ids: HashMap<u64, (u64, u64, usize)>,
+    rejudges: Vec<Rejudge>,
+}
+
+impl Scoreboard {
+    pub fn new(rules: ScoringRules) -> Self {
+        Self {
+            rules,
+            ..Default::default()
+        }
+    }
+
+    /// Scoreboard which hides verdicts of submissions made at or after `freeze`.
+    pub fn with_freeze(rules: ScoringRules, freeze: u64) -> Self {
+        Self {
+            rules,
+            freeze: Some(freeze),
+            ..Default::default()
+        }
+    }
+
+    pub fn rules(&self) -> &ScoringRules {
+        &self.rules
+    }
+
+    pub fn freeze(&self) -> Option<u64> {
+        self.freeze
+    }
+
+    /// Whether some verdicts are still hidden.
+    pub fn is_frozen(&self) -> bool {
+        self.submissions.values().flatten().any(|v| v.hidden)
+    }
+
+    /// Time of the latest applied submission.
+    pub fn last_time(&self) -> u64 {
+        self.last_time
+    }
+
+    /// All rejudges in the order they were applied.
+    pub fn rejudges(&self) -> &[Rejudge] {
+        &self.rejudges
+    }
+
+    /// Applies a new submission or rejudges an earlier one with the same id.
+    pub fn apply(&mut self, state: State) {
+        if let Some(&(participant, task, idx)) = state.id.and_then(|id| self.ids.get(&id)) {
+            return self.rejudge(participant, task, idx, state.result);
+        }
+
+        self.last_time = self.last_time.max(state.time);
+        self.totals
+            .entry(state.participant)
+            .or_insert_with(|| Total::new(state.participant));
+
+        let key = (state.participant, state.task);
+        let hidden = self.is_hidden(&state);
+        let submissions = self.submissions.entry(key).or_default();
+
+        if let Some(id) = state.id {
+            self.ids.insert(id, (key.0, key.1, submissions.len()));
+        }
+        submissions.push(Submission { state, hidden });
+
+        self.recompute(key);
+    }
+
+    fn is_hidden(&self, state: &State) -> bool {
+        self.freeze.is_some_and(|freeze| state.time >= freeze)
+            && !self.revealed.contains(&state.participant)
+            && matches!(state.result, StateResult::Correct | StateResult::Incorrect)
+    }
+
+    fn rejudge(&mut self, participant: u64, task: u64, idx: usize, result: StateResult) {
+        let before = self.ranks();
+
+        let mut state = self.submissions[&(participant, task)][idx].state.clone();
+        let from = std::mem::replace(&mut state.result, result.clone());
+        let hidden = self.is_hidden(&state);
+        let id = state.id.unwrap_or_default();
+
+        if let Some(submission) = self.submissions.get_mut(&(participant, task)).and_then(|v| v.get_mut(idx)) {
+            *submission = Submission { state, hidden };
+        }
+        self.recompute((participant, task));
+
+        let changes = before
+            .into_iter()
+            .filter_map(|(from, total)| {
+                let to = self.rank_of(total.participant)?;
+                (from != to).then_some(RankChange {
+                    participant: total.participant,
+                    from,
+                    to,
+                })
+            })
+            .collect();
+
+        self.rejudges.push(Rejudge {
+            id,
+            participant,
+            task,
+            from,
+            to: result,
+            changes,
+        });
+    }
+
+    /// Refolds the task state from its submissions and updates the participant's total.
+    fn recompute(&mut self, key: (u64, u64)) {
+        let mut task = TaskState::default();
+        self.submissions
+            .get(&key)
+            .into_iter()
+            .flatten()
+            .for_each(|v| match v.hidden {
+                true if !task.is_solved() => task.pending += 1,
+                true => (),
+                false => task.apply(&v.state.result, v.state.time),
+            });
+
+        let (solved, time) = (task.is_solved() as u64, task.time(&self.rules));
+        let old = self.tasks.insert(key, task).unwrap_or_default();
+
+        if let Some(total) = self.totals.get_mut(&key.0) {
+            total.solves = total.solves + solved - old.is_solved() as u64;
+            total.time = total.time + time - old.time(&self.rules);
+        }
+    }
+
+    /// Reveals all hidden verdicts of the lowest ranked participant that has any.
+    pub fn reveal_next(&mut self) -> Option<RankChange> {
+        let frozen = self.submissions
+            .iter()
+            .filter(|(_, v)| v.iter().any(|v| v.hidden))
+            .map(|(key, _)| *key)
+            .collect::<Vec<_>>();
+
+        let participant = self.ranking()
+            .into_iter()
+            .rev()
+            .find(|v| frozen.iter().any(|key| key.0 == v.participant))?
+            .participant;
+        let from = self.rank_of(participant)?;
+
+        self.revealed.insert(participant);
+        frozen
+            .into_iter()
+            .filter(|key| key.0 == participant)
+            .for_each(|key| {
+                self.submissions
+                    .get_mut(&key)
+                    .into_iter()
+                    .flatten()
+                    .for_each(|v| v.hidden = false);
+                self.recompute(key);
+            });
+
+        Some(RankChange {
+            participant,
+            from,
+            to: self.rank_of(participant)?,
+        })
+    }
+
+    /// Reveals all hidden verdicts from the bottom of the ranking to the top.
+    pub fn unfreeze(&mut self) -> Vec<RankChange> {
+        std::iter::from_fn(|| self.reveal_next()).collect()
+    }
+
+    pub fn task(&self, participant: u64, task: u64) -> Option<&TaskState> {
+        self.tasks.get(&(participant, task))
+    }
+
+    /// Sorted ids of all tasks that got at least one submission.
+    pub fn task_ids(&self) -> Vec<u64> {
+        let mut res = self.tasks.keys().map(|&(_, task)| task).collect::<Vec<_>>();
+        res.sort_unstable();
+        res.dedup();
+        res
+    }
+
+    /// Totals of all participants in no particular order.
+    pub fn totals(&self) -> impl Iterator<Item = &Total> {
+        self.totals.values()
+    }
+
+    /// Totals sorted from the first place to the last one.
+    pub fn ranking(&self) -> Vec<Total> {
+        let mut res = self.totals.values().cloned().collect::<Vec<_>>();
+        res.sort_unstable_by(|l, r| l.cmp(r).reverse());
+        res
+    }
+
+    /// Ranking with one-based shared ranks of each participant.
+    pub fn ranks(&self) -> Vec<(usize, Total)> {
+        let ranking = self.ranking();
+        shared_ranks(&ranking).into_iter().zip(ranking).collect()
+    }
+
+    /// One-based place of the participant in the ranking, equal results share the place.
+    pub fn rank_of(&self, participant: u64) -> Option<usize> {
+        let total = self.totals.get(&participant)?;
+        Some(self.totals.values().filter(|v| v.cmp_results(total).is_gt()).count() + 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{RankChange, Scoreboard, ScoringRules, State};
+
+    fn scoreboard(rules: ScoringRules) -> Scoreboard {
+        let mut scoreboard = Scoreboard::new(rules);
+
+        [
+            "1 1 10 I",
+            "1 1 30 C",
+            "1 1 40 C",
+            "1 2 50 I",
+            "2 1 20 C",
+        ]
+        .into_iter()
+        .for_each(|v| scoreboard.apply(State::try_from(v).unwrap()));
+
+        scoreboard
+    }
+
+    #[test]
+    fn test_rules_penalty() {
+        let res = scoreboard(ScoringRules::Classic).ranking();
+        assert_eq!((res[1].participant, res[1].solves, res[1].time), (1, 1, 50));
+
+        let res = scoreboard(ScoringRules::PenaltyAlways(20)).ranking();
+        assert_eq!((res[1].participant, res[1].solves, res[1].time), (1, 1, 70));
+
+        let res = scoreboard(ScoringRules::Custom(5)).ranking();
+        assert_eq!((res[1].participant, res[1].solves, res[1].time), (1, 1, 35));
+    }
+
+    #[test]
+    fn test_freeze_and_reveal() {
+        let mut scoreboard = Scoreboard::with_freeze(ScoringRules::Classic, 100);
+
+        [
+            "1 1 10 C",
+            "2 1 120 I",
+            "2 1 130 C",
+            "2 2 140 C",
+            "3 1 150 I",
+        ]
+        .into_iter()
+        .for_each(|v| scoreboard.apply(State::try_from(v).unwrap()));
+
+        assert_eq!(scoreboard.rank_of(1), Some(1));
+        assert_eq!(scoreboard.task(2, 1).map(|v| v.pending), Some(2));
+        assert!(scoreboard.is_frozen());
+
+        assert_eq!(scoreboard.unfreeze(), vec![
+            RankChange { participant: 2, from: 2, to: 1 },
+            RankChange { participant: 3, from: 3, to: 3 },
+        ]);
+        assert!(!scoreboard.is_frozen());
+
+        let res = scoreboard.ranking();
+        assert_eq!((res[0].participant, res[0].solves, res[0].time), (2, 2, 290));
+        assert_eq!(scoreboard.task(2, 1).map(|v| v.pending), Some(0));
+    }
+
+    #[test]
+    fn test_rejudge() {
+        let mut scoreboard = Scoreboard::new(ScoringRules::Classic);
+
+        ["1 1 10 I id=1", "1 1 20 U id=2", "2 1 15 C id=3", "1 1 40 C id=2", "1 1 50 C id=1"]
+            .into_iter()
+            .for_each(|v| scoreboard.apply(State::try_from(v).unwrap()));
+
+        assert_eq!(scoreboard.task(1, 1).map(|v| (v.tries(), v.pending)), Some((1, 0)));
+        assert_eq!(scoreboard.ranking()[0].participant, 1);
+        assert_eq!(scoreboard.ranking()[0].time, 10);
+        assert_eq!(scoreboard.rejudges().len(), 2);
+        assert_eq!(scoreboard.rejudges()[0].to_string(), "rejudge 2 of 1 on 1: U -> C");
+        assert_eq!(scoreboard.rejudges()[0].changes, vec![]);
+        assert_eq!(scoreboard.rejudges()[1].changes, vec![
+            RankChange { participant: 2, from: 1, to: 2 },
+            RankChange { participant: 1, from: 2, to: 1 },
+        ]);
+    }
+
+    #[test]
+    fn test_rank_of() {
+        let scoreboard = scoreboard(ScoringRules::Classic);
+
+        assert_eq!(scoreboard.rank_of(2), Some(1));
+        assert_eq!(scoreboard.rank_of(1), Some(2));
+        assert_eq!(scoreboard.rank_of(3), None);
+    }
+}
@@ -0,0 +1,193 @@
+use std::{cmp::Ordering, collections::BTreeMap, fmt::Write};
+
+use crate::{Scoreboard, Total};
+
+/// How contest results are combined into the season ranking.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Aggregation {
+    /// Points by shared rank in each contest, e.g. 25, 18, 15, ...; places after the table get none.
+    Points(Vec<u64>),
+    /// Solves and penalty time are summed over contests.
+    Sum,
+}
+
+impl TryFrom<&str> for Aggregation {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "sum" => Ok(Aggregation::Sum),
+            value => value
+                .split(',')
+                .map(|v| v.trim().parse())
+                .collect::<Result<Vec<_>, _>>()
+                .map(Aggregation::Points)
+                .map_err(|_| format!("Expected \"sum\" or comma separated points, found: \"{}\".", value)),
+        }
+    }
+}
+
+/// Result of a participant in a single contest of the season.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContestResult {
+    pub contest: usize,
+    pub rank: usize,
+    pub points: u64,
+    pub total: Total,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeasonEntry {
+    pub participant: u64,
+    pub points: u64,
+    pub solves: u64,
+    pub time: u64,
+    /// Number of contests the participant took part in.
+    pub participations: usize,
+    /// Contests counted into the totals, the best ones if only best N count.
+    pub counted: Vec<ContestResult>,
+}
+
+/// Combined ranking of several contests.
+#[derive(Debug, Clone)]
+pub struct Season {
+    aggregation: Aggregation,
+    best: Option<usize>,
+    results: BTreeMap<u64, Vec<ContestResult>>,
+    contests: usize,
+}
+
+impl Season {
+    /// Counts only `best` results of each participant if set.
+    pub fn new(aggregation: Aggregation, best: Option<usize>) -> Self {
+        Self {
+            aggregation,
+            best,
+            results: BTreeMap::new(),
+            contests: 0,
+        }
+    }
+
+    pub fn contests(&self) -> usize {
+        self.contests
+    }
+
+    pub fn add_contest(&mut self, scoreboard: &Scoreboard) {
+        let contest = self.contests;
+        self.contests += 1;
+
+        scoreboard.ranks().into_iter().for_each(|(rank, total)| {
+            let points = match &self.aggregation {
+                Aggregation::Points(table) => table.get(rank - 1).copied().unwrap_or(0),
+                Aggregation::Sum => 0,
+            };
+
+            self.results.entry(total.participant).or_default().push(ContestResult {
+                contest,
+                rank,
+                points,
+                total,
+            });
+        });
+    }
+
+    fn cmp_results(&self, l: &ContestResult, r: &ContestResult) -> Ordering {
+        match self.aggregation {
+            Aggregation::Points(_) => l.points.cmp(&r.points).then(l.total.cmp_results(&r.total)),
+            Aggregation::Sum => l.total.cmp_results(&r.total),
+        }
+    }
+
+    fn cmp_entries(&self, l: &SeasonEntry, r: &SeasonEntry) -> Ordering {
+        let sums = l.solves.cmp(&r.solves).then(l.time.cmp(&r.time).reverse());
+        match self.aggregation {
+            Aggregation::Points(_) => l.points.cmp(&r.points).then(sums),
+            Aggregation::Sum => sums,
+        }
+    }
+
+    /// Season entries from the first place to the last one.
+    pub fn ranking(&self) -> Vec<SeasonEntry> {
+        let mut res = self.results
+            .iter()
+            .map(|(&participant, results)| {
+                let mut counted = results.clone();
+                counted.sort_by(|l, r| self.cmp_results(l, r).reverse());
+                counted.truncate(self.best.unwrap_or(counted.len()));
+                counted.sort_by_key(|v| v.contest);
+
+                SeasonEntry {
+                    participant,
+                    points: counted.iter().map(|v| v.points).sum(),
+                    solves: counted.iter().map(|v| v.total.solves).sum(),
+                    time: counted.iter().map(|v| v.total.time).sum(),
+                    participations: results.len(),
+                    counted,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        res.sort_by(|l, r| self.cmp_entries(l, r).reverse().then(l.participant.cmp(&r.participant)));
+        res
+    }
+
+    /// Ranking as "rank participant points solves time participations" lines, ties share the rank.
+    pub fn to_text(&self) -> String {
+        let ranking = self.ranking();
+
+        ranking.iter().enumerate().fold(
+            "rank participant points solves time participations\n".to_string(),
+            |mut res, (idx, v)| {
+                let rank = (0..idx)
+                    .rev()
+                    .take_while(|&i| self.cmp_entries(&ranking[i], v).is_eq())
+                    .last()
+                    .unwrap_or(idx) + 1;
+                let _ = writeln!(res, "{} {} {} {} {} {}", rank, v.participant, v.points, v.solves, v.time, v.participations);
+                res
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Aggregation, Scoreboard, ScoringRules, Season, State};
+
+    fn contest(states: &[&str]) -> Scoreboard {
+        let mut scoreboard = Scoreboard::new(ScoringRules::Classic);
+        states.iter().for_each(|v| scoreboard.apply(State::try_from(*v).unwrap()));
+        scoreboard
+    }
+
+    fn season(aggregation: Aggregation, best: Option<usize>) -> Season {
+        let mut season = Season::new(aggregation, best);
+        season.add_contest(&contest(&["1 1 10 C", "2 1 20 C", "3 1 30 I"]));
+        season.add_contest(&contest(&["2 1 10 C", "1 1 20 C"]));
+        season.add_contest(&contest(&["3 1 10 C", "3 2 20 C", "1 1 30 I"]));
+        season
+    }
+
+    #[test]
+    fn test_season_points() {
+        let season = season(Aggregation::try_from("25,18,15").unwrap(), None);
+        let ranking = season.ranking();
+
+        assert_eq!(
+            ranking.iter().map(|v| (v.participant, v.points, v.participations)).collect::<Vec<_>>(),
+            vec![(1, 61, 3), (2, 43, 2), (3, 40, 2)],
+        );
+        assert_eq!(season.to_text().lines().nth(1), Some("1 1 61 2 30 3"));
+    }
+
+    #[test]
+    fn test_season_best_of() {
+        let ranking = season(Aggregation::Sum, Some(1)).ranking();
+
+        assert_eq!(
+            ranking.iter().map(|v| (v.participant, v.solves, v.time)).collect::<Vec<_>>(),
+            vec![(3, 2, 30), (1, 1, 10), (2, 1, 10)],
+        );
+        assert_eq!(ranking[0].counted.iter().map(|v| v.contest).collect::<Vec<_>>(), vec![2]);
+    }
+}
@@ -0,0 +1,193 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use crate::{format::render_html, scoreboard_json, Registry, Scoreboard, Standings, State};
+
+/// Seconds between reloads of the HTML scoreboard.
+const REFRESH: u64 = 10;
+
+/// Time a client has to send its request before the connection is dropped.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Scoreboard shared between the server and the thread feeding submissions into it.
+pub type SharedScoreboard = Arc<Mutex<Scoreboard>>;
+
+/// HTTP server of the live scoreboard, bound to localhost only.
+pub struct Server {
+    listener: TcpListener,
+    scoreboard: SharedScoreboard,
+    registry: Option<Registry>,
+}
+
+impl Server {
+    pub fn bind(port: u16, scoreboard: SharedScoreboard) -> Result<Self, String> {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .map_err(|e| format!("Couldn't bind to port {}: \"{}\"", port, e))?;
+
+        Ok(Self {
+            listener,
+            scoreboard,
+            registry: None,
+        })
+    }
+
+    pub fn set_registry(&mut self, registry: Option<Registry>) {
+        self.registry = registry;
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr, String> {
+        self.listener.local_addr().map_err(|e| e.to_string())
+    }
+
+    /// Serves requests forever, each connection on its own thread,
+    /// so an idle or slow client doesn't hold up the others.
+    pub fn run(&self) -> Result<(), String> {
+        thread::scope(|scope| {
+            self.listener.incoming().try_for_each(|stream| {
+                let stream = stream.map_err(|e| format!("Got an io error: \"{}\"", e))?;
+                // A broken client must not stop the server.
+                scope.spawn(move || self.handle(stream));
+                Ok(())
+            })
+        })
+    }
+
+    fn handle(&self, mut stream: TcpStream) -> std::io::Result<()> {
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut request = String::new();
+        reader.read_line(&mut request)?;
+
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 0 && !header.trim().is_empty() {
+            header.clear();
+        }
+
+        let (status, content_type, body) = match request.split_whitespace().collect::<Vec<_>>()[..] {
+            ["GET", path, ..] => match path {
+                "/" | "/index.html" => ("200 OK", "text/html; charset=utf-8", self.render(|v| render_html(v, Some(REFRESH)))),
+                "/scoreboard.json" => ("200 OK", "application/json", self.render(|v| scoreboard_json(v).to_string())),
+                _ => ("404 Not Found", "text/plain", "Not found\n".to_string()),
+            },
+            [_, _, ..] => ("405 Method Not Allowed", "text/plain", "Method not allowed\n".to_string()),
+            _ => ("400 Bad Request", "text/plain", "Bad request\n".to_string()),
+        };
+
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            content_type,
+            body.len(),
+            body,
+        )?;
+        stream.flush()
+    }
+
+    fn render(&self, render: impl Fn(&Standings) -> String) -> String {
+        let scoreboard = self.scoreboard.lock().unwrap_or_else(|e| e.into_inner());
+        let mut standings = Standings::new(&scoreboard);
+        standings.set_registry(self.registry.as_ref());
+        render(&standings)
+    }
+}
+
+/// Applies submission lines from the reader to the scoreboard.
+/// With `follow` the reader is polled for new lines after its end instead of stopping.
+pub fn ingest<R: BufRead>(mut reader: R, scoreboard: &SharedScoreboard, follow: Option<Duration>) -> Result<(), String> {
+    let mut buffer = String::new();
+    let mut line = 0;
+
+    loop {
+        let read = reader
+            .read_line(&mut buffer)
+            .map_err(|e| format!("Got an io error: \"{}\"", e))?;
+
+        if read == 0 || !buffer.ends_with('\n') {
+            match follow {
+                Some(interval) => {
+                    thread::sleep(interval);
+                    continue;
+                },
+                None if buffer.trim().is_empty() => return Ok(()),
+                None => (),
+            }
+        }
+
+        line += 1;
+        let value = std::mem::take(&mut buffer);
+        if value.trim().is_empty() {
+            continue;
+        }
+
+        let state = State::try_from(value.trim()).map_err(|e| format!("line {}: {}", line, e))?;
+        scoreboard.lock().unwrap_or_else(|e| e.into_inner()).apply(state);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+        sync::{Arc, Mutex},
+        thread,
+        time::Duration,
+    };
+
+    use crate::{ingest, Scoreboard, ScoringRules, Server};
+
+    fn get(addr: std::net::SocketAddr, request: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "{}\r\nHost: localhost\r\n\r\n", request).unwrap();
+
+        let mut res = String::new();
+        stream.read_to_string(&mut res).unwrap();
+        res
+    }
+
+    #[test]
+    fn test_serve_scoreboard() {
+        let scoreboard = Arc::new(Mutex::new(Scoreboard::new(ScoringRules::Classic)));
+        ingest("1 1 10 C\n2 1 20 I\n".as_bytes(), &scoreboard, None).unwrap();
+
+        let server = Server::bind(0, scoreboard.clone()).unwrap();
+        let addr = server.local_addr().unwrap();
+        thread::spawn(move || server.run());
+
+        let res = get(addr, "GET /scoreboard.json HTTP/1.1");
+        assert!(res.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(res.contains("\"team_id\":\"1\""));
+
+        ingest("2 1 30 C\n".as_bytes(), &scoreboard, None).unwrap();
+        let res = get(addr, "GET / HTTP/1.1");
+        assert!(res.contains("<meta http-equiv=\"refresh\""));
+        assert!(res.contains("<td>2</td><td>2/30/20</td>"));
+
+        assert!(get(addr, "GET /missing HTTP/1.1").starts_with("HTTP/1.1 404"));
+        assert!(get(addr, "POST / HTTP/1.1").starts_with("HTTP/1.1 405"));
+    }
+
+    #[test]
+    fn test_serve_with_idle_connection() {
+        let scoreboard = Arc::new(Mutex::new(Scoreboard::new(ScoringRules::Classic)));
+        let server = Server::bind(0, scoreboard).unwrap();
+        let addr = server.local_addr().unwrap();
+        thread::spawn(move || server.run());
+
+        let _idle = TcpStream::connect(addr).unwrap();
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+        write!(stream, "GET /scoreboard.json HTTP/1.1\r\n\r\n").unwrap();
+
+        let mut res = String::new();
+        stream.read_to_string(&mut res).unwrap();
+        assert!(res.starts_with("HTTP/1.1 200 OK\r\n"));
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{shared_ranks, ClicsIds, Medal, Medals, Registry, Scoreboard, Total};
+
+/// Ranked rows of a scoreboard prepared for output.
+#[derive(Debug, Clone)]
+pub struct Standings<'a> {
+    pub scoreboard: &'a Scoreboard,
+    pub ranking: Vec<Total>,
+    pub ranks: Vec<usize>,
+    /// Medal of each row, empty if medals are not awarded.
+    pub medals: Vec<Option<Medal>>,
+    pub registry: Option<&'a Registry>,
+    /// Original ids of an imported event feed.
+    pub ids: Option<&'a ClicsIds>,
+}
+
+impl<'a> Standings<'a> {
+    pub fn new(scoreboard: &'a Scoreboard) -> Self {
+        Self::with_ranking(scoreboard, scoreboard.ranking())
+    }
+
+    /// Standings of a part of the scoreboard, e.g. a single division.
+    pub fn with_ranking(scoreboard: &'a Scoreboard, ranking: Vec<Total>) -> Self {
+        Self {
+            scoreboard,
+            ranks: shared_ranks(&ranking),
+            ranking,
+            medals: vec![],
+            registry: None,
+            ids: None,
+        }
+    }
+
+    pub fn set_registry(&mut self, registry: Option<&'a Registry>) {
+        self.registry = registry;
+    }
+
+    pub fn set_ids(&mut self, ids: Option<&'a ClicsIds>) {
+        self.ids = ids;
+    }
+
+    pub fn set_medals(&mut self, medals: &Medals) {
+        self.medals = medals.award(&self.ranking, &self.ranks);
+    }
+
+    /// Display name of the participant.
+    pub fn name(&self, participant: u64) -> String {
+        self.registry.map_or_else(|| participant.to_string(), |v| v.name(participant))
+    }
+
+    pub fn medal(&self, idx: usize) -> Option<Medal> {
+        self.medals.get(idx).copied().flatten()
+    }
+}
@@ -0,0 +1,163 @@
+use std::{collections::HashMap, convert::TryFrom, fmt::Display};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateResult {
+    Correct,
+    Incorrect,
+    ClarificationRequest,
+    Unjudged,
+    ErroneousSubmission,
+}
+
+impl TryFrom<&str> for StateResult {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "C" => Ok(StateResult::Correct),
+            "I" => Ok(StateResult::Incorrect),
+            "R" => Ok(StateResult::ClarificationRequest),
+            "U" => Ok(StateResult::Unjudged),
+            "E" => Ok(StateResult::ErroneousSubmission),
+            _ => Err(format!("Unknown game result \"{}\".", value)),
+        }
+    }
+}
+
+impl Display for StateResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            StateResult::Correct => "C",
+            StateResult::Incorrect => "I",
+            StateResult::ClarificationRequest => "R",
+            StateResult::Unjudged => "U",
+            StateResult::ErroneousSubmission => "E",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    pub participant: u64,
+    pub task: u64,
+    pub time: u64,
+    pub result: StateResult,
+    /// Submission id, a later state with the same id rejudges the submission.
+    pub id: Option<u64>,
+    /// Points of each subtask for partially scored contests.
+    pub score: Option<Vec<u64>>,
+}
+
+impl State {
+    pub fn new(participant: u64, task: u64, time: u64, result: StateResult) -> Self {
+        Self {
+            participant,
+            task,
+            time,
+            result,
+            id: None,
+            score: None,
+        }
+    }
+
+    /// Parses a submission line, converting its time field with `time`.
+    pub fn parse_with(value: &str, time: impl Fn(&str) -> Result<u64, String>) -> Result<Self, String> {
+        let values = value.split_whitespace().collect::<Vec<_>>();
+
+        if values.len() < 4 {
+            return Err("Expected 4 arguments.".into());
+        }
+
+        let mut res = Self::new(
+            values[0].parse().map_err(|_| "Expected participant id as number".to_string())?,
+            values[1].parse().map_err(|_| "Expected task id as number".to_string())?,
+            time(values[2])?,
+            values[3].try_into()?,
+        );
+
+        values[4..].iter().try_for_each(|field| match field.split_once('=') {
+            Some(("id", id)) => {
+                res.id = Some(id.parse().map_err(|_| "Expected submission id as number".to_string())?);
+                Ok(())
+            },
+            Some(("score", score)) => {
+                res.score = Some(score
+                    .split('+')
+                    .map(|v| v.parse())
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| "Expected score as numbers joined by \"+\"".to_string())?);
+                Ok(())
+            },
+            _ => Err(format!("Unknown field \"{}\".", field)),
+        })?;
+
+        Ok(res)
+    }
+}
+
+impl TryFrom<&str> for State {
+    type Error = String;
+
+    /// Parses "participant task time result" followed by optional "key=value" fields:
+    /// "id=7" and "score=40" or "score=10+0+30" with points of each subtask.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::parse_with(value, |v| v.parse().map_err(|_| "Expected time as number".to_string()))
+    }
+}
+
+/// Replaces verdicts of submissions with the ones of their latest rejudges,
+/// and scores too when a rejudge gives one.
+/// Rejudging states are dropped, so every submission is left once at its original place.
+pub fn resolve_rejudges(states: impl IntoIterator<Item = State>) -> Vec<State> {
+    let mut res: Vec<State> = vec![];
+    let mut ids: HashMap<u64, usize> = HashMap::new();
+
+    states.into_iter().for_each(|state| match state.id.and_then(|id| ids.get(&id)) {
+        Some(&idx) => {
+            res[idx].result = state.result;
+            if state.score.is_some() {
+                res[idx].score = state.score;
+            }
+        },
+        None => {
+            if let Some(id) = state.id {
+                ids.insert(id, res.len());
+            }
+            res.push(state);
+        },
+    });
+
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{resolve_rejudges, State, StateResult};
+
+    #[test]
+    fn test_parse_state() {
+        assert_eq!(State::try_from("1 2 30 C"), Ok(State::new(1, 2, 30, StateResult::Correct)));
+        assert_eq!(
+            State::try_from("1 2 30 U id=7").map(|v| v.id),
+            Ok(Some(7)),
+        );
+        assert_eq!(
+            State::try_from("1 2 30 I score=10+0+5 id=3").map(|v| (v.id, v.score)),
+            Ok((Some(3), Some(vec![10, 0, 5]))),
+        );
+        assert_eq!(State::try_from("1 2 30 U ip=7"), Err("Unknown field \"ip=7\".".into()));
+        assert_eq!(State::try_from("1 2 30"), Err("Expected 4 arguments.".into()));
+    }
+
+    #[test]
+    fn test_resolve_rejudges() {
+        let states = ["1 1 10 I id=1", "2 1 20 U id=2", "1 1 30 C", "2 1 40 C id=2", "1 1 50 C id=1"]
+            .into_iter()
+            .map(|v| State::try_from(v).unwrap());
+
+        assert_eq!(
+            resolve_rejudges(states).iter().map(|v| (v.time, v.result.clone())).collect::<Vec<_>>(),
+            vec![(10, StateResult::Correct), (20, StateResult::Correct), (30, StateResult::Correct)],
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use std::{collections::{BTreeMap, HashMap}, fmt::Write};
+
+use serde_json::{json, Value};
+
+use crate::{State, StateResult};
+
+/// Analytics of a single task.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TaskStats {
+    pub task: u64,
+    /// Judged submissions, both accepted and rejected.
+    pub attempts: u64,
+    pub accepted: u64,
+    /// Number of participants that solved the task.
+    pub solved_by: u64,
+    /// Participant and minute of the first accepted submission.
+    pub first_solve: Option<(u64, u64)>,
+    /// Average minute of the first accepted submission of each participant.
+    pub average_time: Option<f64>,
+}
+
+impl TaskStats {
+    pub fn acceptance_ratio(&self) -> f64 {
+        match self.attempts {
+            0 => 0.0,
+            attempts => self.accepted as f64 / attempts as f64,
+        }
+    }
+}
+
+/// Per-task statistics of a contest.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Statistics {
+    pub tasks: Vec<TaskStats>,
+}
+
+impl<'a> FromIterator<&'a State> for Statistics {
+    fn from_iter<T: IntoIterator<Item = &'a State>>(iter: T) -> Self {
+        let mut tasks: BTreeMap<u64, TaskStats> = BTreeMap::new();
+        let mut solves: HashMap<(u64, u64), u64> = HashMap::new();
+
+        iter.into_iter().for_each(|state| {
+            let stats = tasks.entry(state.task).or_insert_with(|| TaskStats {
+                task: state.task,
+                ..Default::default()
+            });
+
+            match state.result {
+                StateResult::Correct => {
+                    stats.attempts += 1;
+                    stats.accepted += 1;
+
+                    let solve = solves.entry((state.participant, state.task)).or_insert(state.time);
+                    *solve = state.time.min(*solve);
+
+                    if stats.first_solve.is_none_or(|(_, time)| state.time < time) {
+                        stats.first_solve = Some((state.participant, state.time));
+                    }
+                },
+                StateResult::Incorrect => stats.attempts += 1,
+                _ => (),
+            }
+        });
+
+        let mut times: HashMap<u64, u64> = HashMap::new();
+        solves.into_iter().for_each(|((_, task), time)| {
+            *times.entry(task).or_default() += time;
+            if let Some(stats) = tasks.get_mut(&task) {
+                stats.solved_by += 1;
+            }
+        });
+
+        tasks.values_mut().filter(|v| v.solved_by > 0).for_each(|stats| {
+            stats.average_time = Some(times[&stats.task] as f64 / stats.solved_by as f64);
+        });
+
+        Self {
+            tasks: tasks.into_values().collect(),
+        }
+    }
+}
+
+impl Statistics {
+    pub fn to_text(&self) -> String {
+        self.tasks.iter().fold(
+            "task attempts accepted ratio solved_by first_solve average_time\n".to_string(),
+            |mut res, v| {
+                let _ = writeln!(
+                    res,
+                    "{} {} {} {:.2} {} {} {}",
+                    v.task,
+                    v.attempts,
+                    v.accepted,
+                    v.acceptance_ratio(),
+                    v.solved_by,
+                    v.first_solve.map_or("-".into(), |(participant, time)| format!("{}@{}", participant, time)),
+                    v.average_time.map_or("-".into(), |v| format!("{:.1}", v)),
+                );
+                res
+            },
+        )
+    }
+
+    pub fn to_json(&self) -> Value {
+        Value::Array(self.tasks
+            .iter()
+            .map(|v| json!({
+                "task": v.task,
+                "attempts": v.attempts,
+                "accepted": v.accepted,
+                "acceptance_ratio": v.acceptance_ratio(),
+                "solved_by": v.solved_by,
+                "first_solve": v.first_solve.map(|(participant, time)| json!({
+                    "participant": participant,
+                    "time": time,
+                })),
+                "average_time": v.average_time,
+            }))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{State, Statistics, TaskStats};
+
+    #[test]
+    fn test_task_statistics() {
+        let states = [
+            "1 1 10 I",
+            "2 1 15 C",
+            "1 1 25 C",
+            "1 1 30 C",
+            "3 2 40 I",
+            "3 2 45 R",
+        ]
+        .into_iter()
+        .map(|v| State::try_from(v).unwrap())
+        .collect::<Vec<_>>();
+
+        let stats = states.iter().collect::<Statistics>();
+
+        assert_eq!(stats.tasks, vec![
+            TaskStats {
+                task: 1,
+                attempts: 4,
+                accepted: 3,
+                solved_by: 2,
+                first_solve: Some((2, 15)),
+                average_time: Some(20.0),
+            },
+            TaskStats {
+                task: 2,
+                attempts: 1,
+                ..Default::default()
+            },
+        ]);
+        assert_eq!(stats.tasks[0].acceptance_ratio(), 0.75);
+        assert_eq!(stats.to_text().lines().nth(1), Some("1 4 3 0.75 2 2@15 20.0"));
+        assert_eq!(stats.to_json()[1]["first_solve"], serde_json::Value::Null);
+    }
+}
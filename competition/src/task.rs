@@ -0,0 +1,48 @@
+use crate::{ScoringRules, StateResult};
+
+/// Result of a single participant on a single task.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TaskState {
+    pub attempts: u64,
+    pub solved_at: Option<u64>,
+    /// Submissions without known verdicts: hidden by the freeze or not judged yet.
+    pub pending: u64,
+}
+
+impl TaskState {
+    pub fn is_solved(&self) -> bool {
+        self.solved_at.is_some()
+    }
+
+    /// Submissions after the first accepted one are ignored.
+    pub fn apply(&mut self, result: &StateResult, time: u64) {
+        if self.is_solved() {
+            return;
+        }
+
+        match result {
+            StateResult::Correct => self.solved_at = Some(time),
+            StateResult::Incorrect => self.attempts += 1,
+            StateResult::Unjudged => self.pending += 1,
+            _ => (),
+        }
+    }
+
+    /// Number of judged submissions, including the accepted one.
+    pub fn tries(&self) -> u64 {
+        self.attempts + self.is_solved() as u64
+    }
+
+    pub fn penalty(&self, rules: &ScoringRules) -> u64 {
+        if self.is_solved() || rules.charges_unsolved() {
+            self.attempts * rules.penalty()
+        } else {
+            0
+        }
+    }
+
+    /// Time added to the participant's total by this task.
+    pub fn time(&self, rules: &ScoringRules) -> u64 {
+        self.solved_at.unwrap_or(0) + self.penalty(rules)
+    }
+}
@@ -0,0 +1,127 @@
+use std::{fmt::Display, hash::Hash};
+
+#[derive(Debug, Eq, Clone)]
+pub struct Total {
+    pub participant: u64,
+    pub solves: u64,
+    pub time: u64,
+}
+
+impl Total {
+    pub fn new(participant: u64) -> Self {
+        Self {
+            participant,
+            solves: 0,
+            time: 0,
+        }
+    }
+
+    pub fn add_solve(&mut self) {
+        self.solves += 1
+    }
+
+    pub fn add_time(&mut self, time: u64) {
+        self.time += time;
+    }
+
+    /// Compares only the results, so different participants may be equal.
+    pub fn cmp_results(&self, other: &Self) -> std::cmp::Ordering {
+        self.solves.cmp(&other.solves)
+            .then(self.time.cmp(&other.time).reverse())
+    }
+}
+
+/// One-based ranks of a sorted ranking, equal results share the same rank.
+pub fn shared_ranks(ranking: &[Total]) -> Vec<usize> {
+    let mut res: Vec<usize> = Vec::with_capacity(ranking.len());
+
+    ranking.iter().enumerate().for_each(|(idx, total)| {
+        let rank = match idx {
+            0 => 1,
+            _ if ranking[idx - 1].cmp_results(total).is_eq() => res[idx - 1],
+            _ => idx + 1,
+        };
+        res.push(rank);
+    });
+
+    res
+}
+
+impl PartialEq for Total {
+    fn eq(&self, other: &Self) -> bool {
+        self.participant == other.participant
+    }
+} 
+
+impl Ord for Total {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.cmp_results(other)
+            .then(self.participant.cmp(&other.participant))
+    }
+}
+
+impl PartialOrd for Total {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Hash for Total {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.participant.hash(state)
+    }
+}
+
+impl Display for Total {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{} {} {}", self.participant, self.solves, self.time))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cmp::Ordering;
+
+    use crate::{shared_ranks, Total};
+
+    #[test]
+    fn test_total_compare() {
+        let a = Total {
+            participant: 1,
+            solves: 5,
+            time: 666,
+        };
+        let b = Total {
+            participant: 2,
+            solves: 3,
+            time: 1,
+        };
+
+        assert_eq!(a.cmp(&b), Ordering::Greater);
+
+        let b = Total {
+            participant: 2,
+            solves: 5,
+            time: 1000,
+        };
+
+        assert_eq!(a.cmp(&b), Ordering::Greater);
+
+        assert_eq!(a.cmp(&a), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_shared_ranks() {
+        let ranking = [(1, 3, 100), (2, 2, 50), (3, 2, 50), (4, 2, 50), (5, 1, 10)]
+            .into_iter()
+            .map(|(participant, solves, time)| Total {
+                participant,
+                solves,
+                time,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(shared_ranks(&ranking), vec![1, 2, 2, 2, 5]);
+        assert_eq!(shared_ranks(&[]), Vec::<usize>::new());
+    }
+}
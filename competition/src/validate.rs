@@ -0,0 +1,161 @@
+use std::{fmt::Display, ops::RangeInclusive};
+
+use crate::State;
+
+/// Limits of a contest that submissions are checked against, unset limits are not checked.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ContestConfig {
+    /// Contest length in minutes.
+    pub length: Option<u64>,
+    /// Ranges of task ids, kept as given so that wide ones take no memory.
+    pub tasks: Option<Vec<RangeInclusive<u64>>>,
+    pub participants: Option<RangeInclusive<u64>>,
+}
+
+/// Parses "first-last" or a single id.
+pub fn parse_range(value: &str) -> Result<RangeInclusive<u64>, String> {
+    let parse = |v: &str| v
+        .trim()
+        .parse::<u64>()
+        .map_err(|_| format!("Expected id or range of ids, found: \"{}\".", value));
+
+    match value.split_once('-') {
+        Some((first, last)) => match (parse(first)?, parse(last)?) {
+            (first, last) if first > last => Err(format!("Expected range of ids from smaller to larger, found: \"{}\".", value)),
+            (first, last) => Ok(first..=last),
+        },
+        None => parse(value).map(|v| v..=v),
+    }
+}
+
+/// Parses comma separated ids and ranges of ids, e.g. "1-5,7".
+pub fn parse_ids(value: &str) -> Result<Vec<RangeInclusive<u64>>, String> {
+    value.split(',').map(parse_range).collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    NonMonotonicTime { block: usize, line: usize, time: u64, previous: u64 },
+    TimeAfterEnd { block: usize, line: usize, time: u64, length: u64 },
+    UnknownTask { block: usize, line: usize, task: u64 },
+    ParticipantOutOfRange { block: usize, line: usize, participant: u64 },
+}
+
+impl ValidationError {
+    pub fn block(&self) -> usize {
+        match self {
+            ValidationError::NonMonotonicTime { block, .. }
+            | ValidationError::TimeAfterEnd { block, .. }
+            | ValidationError::UnknownTask { block, .. }
+            | ValidationError::ParticipantOutOfRange { block, .. } => *block,
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        match self {
+            ValidationError::NonMonotonicTime { line, .. }
+            | ValidationError::TimeAfterEnd { line, .. }
+            | ValidationError::UnknownTask { line, .. }
+            | ValidationError::ParticipantOutOfRange { line, .. } => *line,
+        }
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("block {}, line {}: ", self.block(), self.line()))?;
+
+        match self {
+            ValidationError::NonMonotonicTime { time, previous, .. } =>
+                f.write_fmt(format_args!("time {} is before previous time {}.", time, previous)),
+            ValidationError::TimeAfterEnd { time, length, .. } =>
+                f.write_fmt(format_args!("time {} is after the end of contest at {}.", time, length)),
+            ValidationError::UnknownTask { task, .. } =>
+                f.write_fmt(format_args!("task {} is not in the problem set.", task)),
+            ValidationError::ParticipantOutOfRange { participant, .. } =>
+                f.write_fmt(format_args!("participant {} is out of range.", participant)),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Checks submissions of a single block in their order.
+#[derive(Debug, Clone)]
+pub struct Validator<'a> {
+    config: &'a ContestConfig,
+    block: usize,
+    previous: Option<u64>,
+}
+
+impl<'a> Validator<'a> {
+    pub fn new(config: &'a ContestConfig, block: usize) -> Self {
+        Self {
+            config,
+            block,
+            previous: None,
+        }
+    }
+
+    /// Returns all problems of the submission on the given line.
+    pub fn check(&mut self, line: usize, state: &State) -> Vec<ValidationError> {
+        let block = self.block;
+        let mut res = vec![];
+
+        if let Some(previous) = self.previous.filter(|&v| v > state.time) {
+            res.push(ValidationError::NonMonotonicTime { block, line, time: state.time, previous });
+        }
+        self.previous = Some(self.previous.map_or(state.time, |v| v.max(state.time)));
+
+        if let Some(length) = self.config.length.filter(|&v| v < state.time) {
+            res.push(ValidationError::TimeAfterEnd { block, line, time: state.time, length });
+        }
+
+        if self.config.tasks.as_ref().is_some_and(|v| !v.iter().any(|v| v.contains(&state.task))) {
+            res.push(ValidationError::UnknownTask { block, line, task: state.task });
+        }
+
+        if self.config.participants.as_ref().is_some_and(|v| !v.contains(&state.participant)) {
+            res.push(ValidationError::ParticipantOutOfRange { block, line, participant: state.participant });
+        }
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{parse_ids, parse_range, ContestConfig, State, StateResult, ValidationError, Validator};
+
+    #[test]
+    fn test_validate_states() {
+        let config = ContestConfig {
+            length: Some(300),
+            tasks: Some(parse_ids("1-3,5").unwrap()),
+            participants: Some(parse_range("1-10").unwrap()),
+        };
+        let mut validator = Validator::new(&config, 2);
+        let state = |participant, task, time| State::new(participant, task, time, StateResult::Correct);
+
+        assert_eq!(validator.check(1, &state(1, 5, 100)), vec![]);
+        assert_eq!(validator.check(2, &state(11, 4, 50)), vec![
+            ValidationError::NonMonotonicTime { block: 2, line: 2, time: 50, previous: 100 },
+            ValidationError::UnknownTask { block: 2, line: 2, task: 4 },
+            ValidationError::ParticipantOutOfRange { block: 2, line: 2, participant: 11 },
+        ]);
+        assert_eq!(validator.check(3, &state(1, 1, 100)), vec![]);
+        assert_eq!(
+            validator.check(4, &state(1, 1, 301)).iter().map(|v| v.to_string()).collect::<Vec<_>>(),
+            vec!["block 2, line 4: time 301 is after the end of contest at 300.".to_string()],
+        );
+    }
+
+    #[test]
+    fn test_parse_ids() {
+        assert_eq!(parse_ids("1-3,7"), Ok(vec![1..=3, 7..=7]));
+        assert_eq!(parse_ids("1-4000000000"), Ok(vec![1..=4000000000]));
+        assert!(parse_ids("1,a").is_err());
+        assert!(parse_ids("5-3").is_err());
+        assert_eq!(parse_range("3-3"), Ok(3..=3));
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use competition::{Scoreboard, ScoringRules, State, StateResult};
+use proptest::prelude::*;
+
+/// Brute-force scorer: (solves, time) of each participant recomputed from scratch.
+fn reference(rules: ScoringRules, states: &[State]) -> BTreeMap<u64, (u64, u64)> {
+    let participants = states.iter().map(|v| v.participant).collect::<BTreeSet<_>>();
+
+    participants
+        .into_iter()
+        .map(|participant| {
+            let tasks = states
+                .iter()
+                .filter(|v| v.participant == participant)
+                .map(|v| v.task)
+                .collect::<BTreeSet<_>>();
+
+            let (solves, time) = tasks.into_iter().fold((0, 0), |(solves, time), task| {
+                let submissions = states
+                    .iter()
+                    .filter(|v| v.participant == participant && v.task == task)
+                    .collect::<Vec<_>>();
+                let accepted = submissions.iter().position(|v| v.result == StateResult::Correct);
+                let before = &submissions[..accepted.unwrap_or(submissions.len())];
+                let incorrect = before.iter().filter(|v| v.result == StateResult::Incorrect).count() as u64;
+
+                match accepted {
+                    Some(idx) => (solves + 1, time + submissions[idx].time + incorrect * rules.penalty()),
+                    None if rules.charges_unsolved() => (solves, time + incorrect * rules.penalty()),
+                    None => (solves, time),
+                }
+            });
+
+            (participant, (solves, time))
+        })
+        .collect()
+}
+
+fn scoreboard(rules: ScoringRules, states: &[State]) -> Scoreboard {
+    let mut scoreboard = Scoreboard::new(rules);
+    states.iter().cloned().for_each(|v| scoreboard.apply(v));
+    scoreboard
+}
+
+fn rules() -> impl Strategy<Value = ScoringRules> {
+    prop_oneof![
+        Just(ScoringRules::Classic),
+        (0..60u64).prop_map(ScoringRules::PenaltyAlways),
+        (0..60u64).prop_map(ScoringRules::Custom),
+    ]
+}
+
+/// Submission logs sorted by time, as the parser guarantees.
+fn log() -> impl Strategy<Value = Vec<State>> {
+    let result = prop_oneof![
+        Just(StateResult::Correct),
+        Just(StateResult::Incorrect),
+        Just(StateResult::Unjudged),
+        Just(StateResult::ErroneousSubmission),
+        Just(StateResult::ClarificationRequest),
+    ];
+
+    prop::collection::vec((1..8u64, 1..6u64, 0..300u64, result), 0..60).prop_map(|mut states| {
+        states.sort_by_key(|v| v.2);
+        states
+            .into_iter()
+            .map(|(participant, task, time, result)| State::new(participant, task, time, result))
+            .collect()
+    })
+}
+
+proptest! {
+    #[test]
+    fn ranking_is_permutation(rules in rules(), states in log()) {
+        let ranking = scoreboard(rules, &states).ranking();
+        let participants = ranking.iter().map(|v| v.participant).collect::<BTreeSet<_>>();
+
+        prop_assert_eq!(participants.len(), ranking.len());
+        prop_assert_eq!(participants, states.iter().map(|v| v.participant).collect::<BTreeSet<_>>());
+    }
+
+    #[test]
+    fn solves_bounded_by_tasks(rules in rules(), states in log()) {
+        let scoreboard = scoreboard(rules, &states);
+
+        for total in scoreboard.totals() {
+            let tasks = states
+                .iter()
+                .filter(|v| v.participant == total.participant)
+                .map(|v| v.task)
+                .collect::<BTreeSet<_>>();
+            prop_assert!(total.solves <= tasks.len() as u64);
+        }
+    }
+
+    #[test]
+    fn matches_reference(rules in rules(), states in log()) {
+        let totals = scoreboard(rules, &states)
+            .totals()
+            .map(|v| (v.participant, (v.solves, v.time)))
+            .collect::<BTreeMap<_, _>>();
+
+        prop_assert_eq!(totals, reference(rules, &states));
+    }
+
+    #[test]
+    fn ranks_match_reference(rules in rules(), states in log()) {
+        let expected = reference(rules, &states);
+        let better = |(solves, time): (u64, u64)| expected
+            .values()
+            .filter(|&&(s, t)| s > solves || (s == solves && t < time))
+            .count() + 1;
+
+        for (rank, total) in scoreboard(rules, &states).ranks() {
+            prop_assert_eq!(rank, better(expected[&total.participant]));
+        }
+    }
+
+    #[test]
+    fn invariant_under_relabeling(
+        rules in rules(),
+        states in log(),
+        labels in Just((1..8u64).collect::<Vec<_>>()).prop_shuffle(),
+    ) {
+        let labels = (1..8u64).zip(labels).collect::<HashMap<_, _>>();
+        let relabeled = states
+            .iter()
+            .cloned()
+            .map(|v| State { participant: labels[&v.participant], ..v })
+            .collect::<Vec<_>>();
+
+        let ranks = |states: &[State]| scoreboard(rules, states)
+            .ranks()
+            .into_iter()
+            .map(|(rank, v)| (rank, v.solves, v.time))
+            .collect::<Vec<_>>();
+        prop_assert_eq!(ranks(&states), ranks(&relabeled));
+
+        let original = scoreboard(rules, &states);
+        let relabeled = scoreboard(rules, &relabeled);
+        for participant in states.iter().map(|v| v.participant) {
+            prop_assert_eq!(original.rank_of(participant), relabeled.rank_of(labels[&participant]));
+        }
+    }
+
+    #[test]
+    fn invariant_under_interleaving(rules in rules(), states in log(), seed in any::<u64>()) {
+        // Submissions of different participants at the same minute may come in any order.
+        let mut shuffled = states.clone();
+        shuffled.sort_by_key(|v| (v.time, (v.participant ^ seed).wrapping_mul(0x9e37_79b9_7f4a_7c15)));
+
+        prop_assert_eq!(scoreboard(rules, &states).ranks(), scoreboard(rules, &shuffled).ranks());
+    }
+}
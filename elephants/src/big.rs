@@ -0,0 +1,78 @@
+use std::{fmt::Display, ops::AddAssign};
+
+const BASE: u64 = 1_000_000_000;
+
+/// Unsigned integer of arbitrary size, enough to count chains.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BigUint {
+    /// Little-endian digits in base 10^9, no trailing zeros.
+    digits: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn is_zero(&self) -> bool {
+	self.digits.is_empty()
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(mut value: u64) -> Self {
+	let mut digits = vec![];
+	while value > 0 {
+	    digits.push((value % BASE) as u32);
+	    value /= BASE;
+	}
+	Self { digits }
+    }
+}
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, rhs: &BigUint) {
+	if self.digits.len() < rhs.digits.len() {
+	    self.digits.resize(rhs.digits.len(), 0);
+	}
+
+	let mut carry = 0;
+	for (idx, digit) in self.digits.iter_mut().enumerate() {
+	    let sum = *digit as u64 + rhs.digits.get(idx).copied().unwrap_or(0) as u64 + carry;
+	    *digit = (sum % BASE) as u32;
+	    carry = sum / BASE;
+
+	    if carry == 0 && idx >= rhs.digits.len() {
+		break;
+	    }
+	}
+
+	if carry > 0 {
+	    self.digits.push(carry as u32);
+	}
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+	match self.digits.split_last() {
+	    None => f.write_str("0"),
+	    Some((last, rest)) => {
+		write!(f, "{}", last)?;
+		rest.iter().rev().try_for_each(|v| write!(f, "{:09}", v))
+	    },
+	}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::BigUint;
+
+    #[test]
+    fn test_big_uint() {
+	let mut value = BigUint::from(u64::MAX);
+	value += &BigUint::from(u64::MAX);
+	value += &BigUint::from(2);
+
+	assert_eq!(value.to_string(), "36893488147419103232");
+	assert_eq!(BigUint::default().to_string(), "0");
+	assert_eq!(BigUint::from(1_000_000_007).to_string(), "1000000007");
+    }
+}
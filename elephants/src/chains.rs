@@ -0,0 +1,210 @@
+use crate::{BigUint, Direction};
+
+/// All longest chains of items under two keys, counted and enumerated without storing them.
+pub struct Chains<A, B> {
+    keys: Vec<(A, B)>,
+    directions: (Direction, Direction),
+    /// Position of each item in the order chains follow, to count equal items once.
+    rank: Vec<usize>,
+    /// Length of the longest chain ending at each item.
+    ending: Vec<usize>,
+    /// Length of the longest chain starting at each item.
+    starting: Vec<usize>,
+    length: usize,
+}
+
+impl<A: Ord, B: Ord> Chains<A, B> {
+    /// Takes O(n^2) comparisons.
+    pub fn new<T>(
+	items: &[T],
+	(first, first_dir): (impl Fn(&T) -> A, Direction),
+	(second, second_dir): (impl Fn(&T) -> B, Direction),
+    ) -> Self {
+	let keys = items.iter().map(|v| (first(v), second(v))).collect::<Vec<_>>();
+
+	let mut order = (0..items.len()).collect::<Vec<_>>();
+	order.sort_by(|&l, &r| first_dir
+	    .cmp(&keys[l].0, &keys[r].0)
+	    .then(second_dir.cmp(&keys[l].1, &keys[r].1)));
+
+	let mut rank = vec![0; items.len()];
+	order.iter().enumerate().for_each(|(pos, &idx)| rank[idx] = pos);
+
+	let mut res = Self {
+	    keys,
+	    directions: (first_dir, second_dir),
+	    rank,
+	    ending: vec![1; items.len()],
+	    starting: vec![1; items.len()],
+	    length: 0,
+	};
+
+	for (pos, &idx) in order.iter().enumerate() {
+	    res.ending[idx] = order[..pos]
+		.iter()
+		.filter(|&&prev| res.follows(prev, idx))
+		.map(|&prev| res.ending[prev] + 1)
+		.max()
+		.unwrap_or(1);
+	}
+
+	for (pos, &idx) in order.iter().enumerate().rev() {
+	    res.starting[idx] = order[pos + 1..]
+		.iter()
+		.filter(|&&next| res.follows(idx, next))
+		.map(|&next| res.starting[next] + 1)
+		.max()
+		.unwrap_or(1);
+	}
+
+	res.length = res.ending.iter().copied().max().unwrap_or(0);
+	res
+    }
+
+    /// Whether item `next` may directly follow item `prev` in a chain.
+    fn follows(&self, prev: usize, next: usize) -> bool {
+	self.rank[prev] < self.rank[next]
+	    && self.directions.0.fits(&self.keys[prev].0, &self.keys[next].0)
+	    && self.directions.1.fits(&self.keys[prev].1, &self.keys[next].1)
+    }
+
+    /// Whether `next` continues a longest chain after `prev`, or starts one without it.
+    fn continues(&self, prev: Option<usize>, next: usize) -> bool {
+	match prev {
+	    None => self.ending[next] == 1 && self.starting[next] == self.length,
+	    Some(prev) => self.follows(prev, next)
+		&& self.ending[next] == self.ending[prev] + 1
+		&& self.starting[next] + 1 == self.starting[prev],
+	}
+    }
+
+    pub fn length(&self) -> usize {
+	self.length
+    }
+
+    /// Number of distinct longest chains, zero without items.
+    pub fn count(&self) -> BigUint {
+	let mut order = (0..self.keys.len()).collect::<Vec<_>>();
+	order.sort_by_key(|&idx| self.rank[idx]);
+
+	let mut ways = vec![BigUint::default(); self.keys.len()];
+	let mut res = BigUint::default();
+
+	for (pos, &idx) in order.iter().enumerate() {
+	    if self.ending[idx] == 1 {
+		ways[idx] = BigUint::from(1);
+	    }
+
+	    for &prev in &order[..pos] {
+		if self.follows(prev, idx) && self.ending[prev] + 1 == self.ending[idx] {
+		    let prev = ways[prev].clone();
+		    ways[idx] += &prev;
+		}
+	    }
+
+	    if self.ending[idx] == self.length {
+		res += &ways[idx];
+	    }
+	}
+
+	res
+    }
+
+    /// Longest chains as item positions, in lexicographic order of the positions.
+    pub fn iter(&self) -> ChainIter<'_, A, B> {
+	ChainIter {
+	    chains: self,
+	    path: vec![],
+	    started: false,
+	}
+    }
+
+    /// Lexicographically smallest longest chain by item positions.
+    pub fn smallest(&self) -> Option<Vec<usize>> {
+	self.iter().next()
+    }
+}
+
+/// Lazily walks longest chains depth first, trying items by their positions.
+pub struct ChainIter<'a, A, B> {
+    chains: &'a Chains<A, B>,
+    path: Vec<usize>,
+    started: bool,
+}
+
+impl<A: Ord, B: Ord> ChainIter<'_, A, B> {
+    /// Pushes the first item after position `from` continuing the path and completes
+    /// the path with the smallest items. Every such item is part of a longest chain,
+    /// so the completion never gets stuck.
+    fn advance(&mut self, from: usize) -> bool {
+	let mut from = from;
+
+	while self.path.len() < self.chains.length {
+	    let prev = self.path.last().copied();
+	    match (from..self.chains.keys.len()).find(|&idx| self.chains.continues(prev, idx)) {
+		Some(idx) => self.path.push(idx),
+		None => return false,
+	    }
+	    from = 0;
+	}
+
+	true
+    }
+}
+
+impl<A: Ord, B: Ord> Iterator for ChainIter<'_, A, B> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+	if !self.started {
+	    self.started = true;
+	    return (self.chains.length > 0 && self.advance(0)).then(|| self.path.clone());
+	}
+
+	loop {
+	    let last = self.path.pop()?;
+	    if self.advance(last + 1) {
+		return Some(self.path.clone());
+	    }
+	}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Chains, Direction};
+
+    #[test]
+    fn test_chains() {
+	let items = [(1, 5), (2, 4), (2, 3), (3, 2), (3, 1), (4, 0), (0, 9)];
+	let chains = Chains::new(&items, (|v: &(u8, u8)| v.0, Direction::Increasing),
+				 (|v: &(u8, u8)| v.1, Direction::Decreasing));
+
+	assert_eq!(chains.length(), 5);
+	assert_eq!(chains.count().to_string(), "4");
+	assert_eq!(chains.iter().collect::<Vec<_>>(), vec![
+	    vec![6, 0, 1, 3, 5],
+	    vec![6, 0, 1, 4, 5],
+	    vec![6, 0, 2, 3, 5],
+	    vec![6, 0, 2, 4, 5],
+	]);
+	assert_eq!(chains.smallest(), Some(vec![6, 0, 1, 3, 5]));
+    }
+
+    #[test]
+    fn test_chains_blow_up() {
+	// 100 layers of two equal elephants give 2^100 chains.
+	let items = (0..200u64).map(|v| (v / 2, 1000 - v / 2)).collect::<Vec<_>>();
+	let chains = Chains::new(&items, (|v: &(u64, u64)| v.0, Direction::Increasing),
+				 (|v: &(u64, u64)| v.1, Direction::Decreasing));
+
+	assert_eq!(chains.length(), 100);
+	assert_eq!(chains.count().to_string(), "1267650600228229401496703205376");
+	assert_eq!(chains.iter().take(3).map(|v| v[99]).collect::<Vec<_>>(), vec![198, 199, 198]);
+
+	let empty: [(u64, u64); 0] = [];
+	let chains = Chains::new(&empty, (|v: &(u64, u64)| v.0, Direction::Increasing),
+				 (|v: &(u64, u64)| v.1, Direction::Decreasing));
+	assert_eq!((chains.count().to_string(), chains.smallest()), ("0".to_string(), None));
+    }
+}
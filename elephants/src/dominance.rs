@@ -0,0 +1,210 @@
+use std::cmp::Ordering;
+
+use crate::Direction;
+
+/// Best chain end found for an item: (chain length, item).
+type Best = Option<(usize, usize)>;
+
+#[derive(Debug, Clone, Copy)]
+enum Event {
+    /// An item with a known chain length ending at it.
+    Update(usize, usize),
+    /// An item looking for the longest chain it can continue.
+    Query(usize),
+}
+
+/// Dense ranks of keys, equal keys get equal ranks and earlier ranks come first in a chain.
+fn ranks<K: Ord>(keys: &[&K], direction: Direction) -> Vec<usize> {
+    let mut order = (0..keys.len()).collect::<Vec<_>>();
+    order.sort_by(|&l, &r| direction.cmp(keys[l], keys[r]));
+
+    let mut res = vec![0; keys.len()];
+    for pos in 1..order.len() {
+	let same = direction.cmp(keys[order[pos - 1]], keys[order[pos]]).is_eq();
+	res[order[pos]] = res[order[pos - 1]] + !same as usize;
+    }
+    res
+}
+
+struct Solver<'a> {
+    /// Rank of each item in each dimension.
+    ranks: &'a [Vec<usize>],
+    strict: &'a [bool],
+    length: Vec<usize>,
+    prev: Vec<Option<usize>>,
+}
+
+impl Solver<'_> {
+    /// Sorts events by rank in `dim`. On equal ranks queries go first under a strict
+    /// direction, so an update only precedes the queries it may be followed by.
+    fn sort(&self, events: &mut [Event], dim: usize) {
+	events.sort_by_key(|event| match *event {
+	    Event::Update(idx, _) => (self.ranks[dim][idx], self.strict[dim]),
+	    Event::Query(idx) => (self.ranks[dim][idx], !self.strict[dim]),
+	});
+    }
+
+    /// For every query the longest update dominated by it in all `dims`, by dividing
+    /// on the first dimension and passing updates of the lower half to queries of the upper one.
+    /// Events come sorted by the first dimension, and so do both halves.
+    fn dominated(&self, events: &mut [Event], dims: &[usize], best: &mut [Best]) {
+	let has_update = events.iter().any(|v| matches!(v, Event::Update(..)));
+	let has_query = events.iter().any(|v| matches!(v, Event::Query(..)));
+	if !has_update || !has_query {
+	    return;
+	}
+
+	if dims.len() == 1 {
+	    let mut current: Best = None;
+	    events.iter().for_each(|event| match *event {
+		Event::Update(idx, length) => current = current.max(Some((length, idx))),
+		Event::Query(idx) => best[idx] = best[idx].max(current),
+	    });
+	    return;
+	}
+
+	let (lower, upper) = events.split_at_mut(events.len() / 2);
+	let mut cross = lower
+	    .iter()
+	    .filter(|v| matches!(v, Event::Update(..)))
+	    .chain(upper.iter().filter(|v| matches!(v, Event::Query(..))))
+	    .copied()
+	    .collect::<Vec<_>>();
+	self.sort(&mut cross, dims[1]);
+	self.dominated(&mut cross, &dims[1..], best);
+
+	self.dominated(lower, dims, best);
+	self.dominated(upper, dims, best);
+    }
+
+    /// Items of `order` are sorted so that earlier ones may only precede later ones.
+    /// Lengths of the lower half are final before they are passed on. `best` is shared
+    /// by all calls and left empty for the items of `order`.
+    fn solve(&mut self, order: &[usize], dims: &[usize], best: &mut [Best]) {
+	if order.len() <= 1 {
+	    return;
+	}
+
+	let (lower, upper) = order.split_at(order.len() / 2);
+	self.solve(lower, dims, best);
+
+	let mut events = lower
+	    .iter()
+	    .map(|&idx| Event::Update(idx, self.length[idx]))
+	    .chain(upper.iter().map(|&idx| Event::Query(idx)))
+	    .collect::<Vec<_>>();
+	self.sort(&mut events, dims[0]);
+	self.dominated(&mut events, dims, best);
+
+	upper.iter().for_each(|&idx| {
+	    if let Some((length, prev)) = best[idx].take().filter(|&(length, _)| length + 1 > self.length[idx]) {
+		self.length[idx] = length + 1;
+		self.prev[idx] = Some(prev);
+	    }
+	});
+
+	self.solve(upper, dims, best);
+    }
+}
+
+/// Positions in `points` of the longest chain where every coordinate changes in its direction,
+/// ordered along the chain. Every point has a key for each direction.
+/// Takes O(n log^(k+1) n) for k directions, every level of division sorts once more.
+pub fn dominance_chain<K: Ord>(points: &[Vec<K>], directions: &[Direction]) -> Vec<usize> {
+    let ranks = directions
+	.iter()
+	.enumerate()
+	.map(|(dim, &direction)| ranks(&points.iter().map(|v| &v[dim]).collect::<Vec<_>>(), direction))
+	.collect::<Vec<_>>();
+    let strict = directions.iter().map(|v| v.is_strict()).collect::<Vec<_>>();
+
+    // Sorted by all ranks, a chain can only go forward; equal points keep their positions.
+    let mut order = (0..points.len()).collect::<Vec<_>>();
+    order.sort_by(|&l, &r| ranks.iter().fold(Ordering::Equal, |res, v| res.then(v[l].cmp(&v[r]))));
+
+    // A non-strict first key is already satisfied by the order.
+    let dims = (0..directions.len()).filter(|&dim| dim > 0 || strict[0]).collect::<Vec<_>>();
+
+    let mut solver = Solver {
+	ranks: &ranks,
+	strict: &strict,
+	length: vec![1; points.len()],
+	prev: vec![None; points.len()],
+    };
+    match dims.is_empty() {
+	true => (1..order.len()).for_each(|pos| {
+	    solver.length[order[pos]] = pos + 1;
+	    solver.prev[order[pos]] = Some(order[pos - 1]);
+	}),
+	false => solver.solve(&order, &dims, &mut vec![None; points.len()]),
+    }
+
+    let mut res = vec![];
+    let mut cur = (0..points.len()).max_by_key(|&idx| solver.length[idx]);
+    while let Some(idx) = cur {
+	res.push(idx);
+	cur = solver.prev[idx];
+    }
+    res.reverse();
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{dominance_chain, longest_chain, Direction};
+
+    #[test]
+    fn test_dominance_matches_two_keys() {
+	let directions = [Direction::Increasing, Direction::NonDecreasing,
+			  Direction::Decreasing, Direction::NonIncreasing];
+	let items = (0..60u64).map(|v| (v * 7 % 11, v * 5 % 13)).collect::<Vec<_>>();
+	let points = items.iter().map(|v| vec![v.0, v.1]).collect::<Vec<_>>();
+
+	for first in directions {
+	    for second in directions {
+		let chain = dominance_chain(&points, &[first, second]);
+		let expected = longest_chain(&items, (|v: &(u64, u64)| v.0, first), (|v: &(u64, u64)| v.1, second));
+
+		assert_eq!(chain.len(), expected.len(), "{:?} {:?}", first, second);
+		assert!(chain.windows(2).all(|v| first.fits(&items[v[0]].0, &items[v[1]].0)
+					   && second.fits(&items[v[0]].1, &items[v[1]].1)));
+	    }
+	}
+    }
+
+    #[test]
+    fn test_dominance_three_keys() {
+	let points = vec![
+	    vec![1, 9, 1], vec![2, 8, 2], vec![3, 7, 1], vec![3, 6, 3],
+	    vec![4, 5, 4], vec![5, 5, 3], vec![6, 1, 6], vec![0, 0, 0],
+	];
+	let chain = dominance_chain(&points, &[Direction::Increasing, Direction::Decreasing, Direction::Increasing]);
+
+	assert_eq!(chain, vec![0, 1, 3, 4, 6]);
+	assert_eq!(dominance_chain::<u64>(&[], &[Direction::Increasing]), vec![]);
+    }
+
+    #[test]
+    fn test_dominance_four_keys() {
+	let directions = [Direction::Increasing, Direction::NonIncreasing,
+			  Direction::Decreasing, Direction::NonDecreasing];
+	let points = (0..80u64).map(|v| vec![v * 7 % 9, v * 5 % 7, v * 3 % 11, v % 5]).collect::<Vec<_>>();
+	let follows = |l: &Vec<u64>, r: &Vec<u64>| (0..4).all(|d| directions[d].fits(&l[d], &r[d]));
+
+	// Quadratic reference over points sorted by the first key.
+	let mut order = (0..points.len()).collect::<Vec<_>>();
+	order.sort_by_key(|&v| points[v][0]);
+	let mut length = vec![1; points.len()];
+	for i in 0..order.len() {
+	    for j in 0..i {
+		if follows(&points[order[j]], &points[order[i]]) {
+		    length[order[i]] = length[order[i]].max(length[order[j]] + 1);
+		}
+	    }
+	}
+
+	let chain = dominance_chain(&points, &directions);
+	assert_eq!(Some(&chain.len()), length.iter().max());
+	assert!(chain.windows(2).all(|v| follows(&points[v[0]], &points[v[1]])));
+    }
+}
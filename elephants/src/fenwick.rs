@@ -0,0 +1,34 @@
+/// Fenwick tree of prefix maximums over ranks, each value remembers the item it came from.
+pub(crate) struct Fenwick {
+    tree: Vec<Option<(u128, usize)>>,
+}
+
+impl Fenwick {
+    pub(crate) fn new(len: usize) -> Self {
+	Self { tree: vec![None; len + 1] }
+    }
+
+    /// Raises the value at `rank` to `value` of `item`.
+    pub(crate) fn update(&mut self, rank: usize, value: u128, item: usize) {
+	let mut idx = rank + 1;
+	while idx < self.tree.len() {
+	    if self.tree[idx].is_none_or(|(v, _)| v < value) {
+		self.tree[idx] = Some((value, item));
+	    }
+	    idx += idx & idx.wrapping_neg();
+	}
+    }
+
+    /// Largest value at ranks below `end`.
+    pub(crate) fn max_before(&self, end: usize) -> Option<(u128, usize)> {
+	let mut idx = end.min(self.tree.len() - 1);
+	let mut res: Option<(u128, usize)> = None;
+	while idx > 0 {
+	    if self.tree[idx].is_some_and(|(v, _)| res.is_none_or(|(r, _)| v > r)) {
+		res = self.tree[idx];
+	    }
+	    idx -= idx & idx.wrapping_neg();
+	}
+	res
+    }
+}
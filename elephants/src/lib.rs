@@ -0,0 +1,220 @@
+use std::cmp::Ordering;
+
+mod big;
+mod chains;
+mod dominance;
+mod fenwick;
+mod online;
+mod partition;
+
+pub use big::BigUint;
+pub use chains::{ChainIter, Chains};
+pub use dominance::dominance_chain;
+pub use online::OnlineChain;
+pub use partition::chain_partition;
+
+use fenwick::Fenwick;
+
+/// How a key changes along a chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Increasing,
+    NonDecreasing,
+    Decreasing,
+    NonIncreasing,
+}
+
+impl Direction {
+    pub fn is_strict(self) -> bool {
+	matches!(self, Direction::Increasing | Direction::Decreasing)
+    }
+
+    /// Whether `next` may follow `prev` in a chain.
+    pub fn fits<K: Ord>(self, prev: &K, next: &K) -> bool {
+	match self {
+	    Direction::Increasing => prev < next,
+	    Direction::NonDecreasing => prev <= next,
+	    Direction::Decreasing => prev > next,
+	    Direction::NonIncreasing => prev >= next,
+	}
+    }
+
+    /// Order of keys along a chain: `Less` means `l` comes first.
+    pub fn cmp<K: Ord>(self, l: &K, r: &K) -> Ordering {
+	match self {
+	    Direction::Increasing | Direction::NonDecreasing => l.cmp(r),
+	    Direction::Decreasing | Direction::NonIncreasing => r.cmp(l),
+	}
+    }
+}
+
+/// Positions in `items` of the longest chain where both keys change in their directions,
+/// ordered along the chain. Takes O(n log n).
+pub fn longest_chain<T, A: Ord, B: Ord>(
+    items: &[T],
+    (first, first_dir): (impl Fn(&T) -> A, Direction),
+    (second, second_dir): (impl Fn(&T) -> B, Direction),
+) -> Vec<usize> {
+    let keys = items.iter().map(|v| (first(v), second(v))).collect::<Vec<_>>();
+
+    let mut order = (0..items.len()).collect::<Vec<_>>();
+    order.sort_by(|&l, &r| first_dir
+	.cmp(&keys[l].0, &keys[r].0)
+	.then(second_dir.cmp(&keys[l].1, &keys[r].1)));
+
+    // tails[k] ends the most permissive chain of length k + 1 found so far.
+    let mut tails: Vec<usize> = Vec::new();
+    let mut prev = vec![None; items.len()];
+
+    // Items with equal first keys can't follow each other under a strict direction,
+    // so the whole group is measured against the chains built before it.
+    let mut start = 0;
+    while start < order.len() {
+	let end = match first_dir.is_strict() {
+	    true => order[start..].partition_point(|&v| keys[v].0 == keys[order[start]].0) + start,
+	    false => start + 1,
+	};
+
+	let lens = order[start..end]
+	    .iter()
+	    .map(|&idx| {
+		let len = tails.partition_point(|&t| second_dir.fits(&keys[t].1, &keys[idx].1));
+		prev[idx] = len.checked_sub(1).map(|v| tails[v]);
+		(idx, len)
+	    })
+	    .collect::<Vec<_>>();
+
+	lens.into_iter().for_each(|(idx, len)| match tails.get_mut(len) {
+	    Some(tail) if second_dir.cmp(&keys[idx].1, &keys[*tail].1).is_gt() => (),
+	    Some(tail) => *tail = idx,
+	    None => tails.push(idx),
+	});
+
+	start = end;
+    }
+
+    let mut res = Vec::with_capacity(tails.len());
+    let mut cur = tails.last().copied();
+    while let Some(idx) = cur {
+	res.push(idx);
+	cur = prev[idx];
+    }
+    res.reverse();
+    res
+}
+
+/// Total value and positions in `items` of the chain with the largest sum of `value`
+/// where both keys change in their directions, ordered along the chain. The total is summed
+/// in `u128`, so it can't overflow. Takes O(n log n).
+pub fn heaviest_chain<T, A: Ord, B: Ord>(
+    items: &[T],
+    (first, first_dir): (impl Fn(&T) -> A, Direction),
+    (second, second_dir): (impl Fn(&T) -> B, Direction),
+    value: impl Fn(&T) -> u64,
+) -> (u128, Vec<usize>) {
+    let keys = items.iter().map(|v| (first(v), second(v))).collect::<Vec<_>>();
+
+    let mut order = (0..items.len()).collect::<Vec<_>>();
+    order.sort_by(|&l, &r| first_dir
+	.cmp(&keys[l].0, &keys[r].0)
+	.then(second_dir.cmp(&keys[l].1, &keys[r].1)));
+
+    // Dense ranks of the second keys in the chain order.
+    let mut by_second = (0..items.len()).collect::<Vec<_>>();
+    by_second.sort_by(|&l, &r| second_dir.cmp(&keys[l].1, &keys[r].1));
+    let mut rank = vec![0; items.len()];
+    for pos in 1..by_second.len() {
+	let same = second_dir.cmp(&keys[by_second[pos - 1]].1, &keys[by_second[pos]].1).is_eq();
+	rank[by_second[pos]] = rank[by_second[pos - 1]] + !same as usize;
+    }
+
+    let mut tree = Fenwick::new(items.len());
+    let mut total = vec![0; items.len()];
+    let mut prev = vec![None; items.len()];
+
+    // Same grouping as in `longest_chain`: equal first keys under a strict direction
+    // are all measured before any of them is added.
+    let mut start = 0;
+    while start < order.len() {
+	let end = match first_dir.is_strict() {
+	    true => order[start..].partition_point(|&v| keys[v].0 == keys[order[start]].0) + start,
+	    false => start + 1,
+	};
+
+	for &idx in &order[start..end] {
+	    let end = rank[idx] + !second_dir.is_strict() as usize;
+	    let best = tree.max_before(end);
+	    total[idx] = best.map_or(0, |v| v.0) + u128::from(value(&items[idx]));
+	    prev[idx] = best.map(|v| v.1);
+	}
+	for &idx in &order[start..end] {
+	    tree.update(rank[idx], total[idx], idx);
+	}
+
+	start = end;
+    }
+
+    let mut res = vec![];
+    let mut cur = (0..items.len()).max_by_key(|&idx| (total[idx], std::cmp::Reverse(idx)));
+    let sum = cur.map_or(0, |idx| total[idx]);
+    while let Some(idx) = cur {
+	res.push(idx);
+	cur = prev[idx];
+    }
+    res.reverse();
+    (sum, res)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{heaviest_chain, longest_chain, Direction};
+
+    /// Length of the longest chain by trying every subset.
+    fn brute_force(items: &[(u8, u8)], first: Direction, second: Direction) -> usize {
+	(0u32..1 << items.len())
+	    .filter_map(|mask| {
+		let mut chain = (0..items.len()).filter(|i| mask >> i & 1 == 1).map(|i| items[i]).collect::<Vec<_>>();
+		chain.sort_by(|l, r| first.cmp(&l.0, &r.0).then(second.cmp(&l.1, &r.1)));
+		chain.windows(2)
+		    .all(|v| first.fits(&v[0].0, &v[1].0) && second.fits(&v[0].1, &v[1].1))
+		    .then_some(chain.len())
+	    })
+	    .max()
+	    .unwrap_or(0)
+    }
+
+    #[test]
+    fn test_directions() {
+	let items = [(1, 3), (2, 2), (2, 3), (3, 1), (3, 3), (1, 1), (2, 1), (4, 2), (4, 4), (0, 2)];
+	let directions = [Direction::Increasing, Direction::NonDecreasing,
+			  Direction::Decreasing, Direction::NonIncreasing];
+
+	for first in directions {
+	    for second in directions {
+		let chain = longest_chain(&items, (|v: &(u8, u8)| v.0, first), (|v: &(u8, u8)| v.1, second));
+
+		assert_eq!(chain.len(), brute_force(&items, first, second), "{:?} {:?}", first, second);
+		assert!(chain.windows(2).all(|v| first.fits(&items[v[0]].0, &items[v[1]].0)
+					   && second.fits(&items[v[0]].1, &items[v[1]].1)));
+	    }
+	}
+    }
+
+    #[test]
+    fn test_heaviest_chain() {
+	let items = [(1, 9, 1), (2, 8, 1), (3, 7, 1), (4, 6, 1), (2, 1, 10), (5, 0, 2), (3, 1, 5), (1, 2, 4)];
+	let first = (|v: &(u8, u8, u64)| v.0, Direction::Increasing);
+	let second = (|v: &(u8, u8, u64)| v.1, Direction::Decreasing);
+
+	assert_eq!(heaviest_chain(&items, first, second, |v| v.2), (16, vec![7, 4, 5]));
+	assert_eq!(heaviest_chain(&items, first, second, |_| 1).0, longest_chain(&items, first, second).len() as u128);
+	assert_eq!(heaviest_chain(&items, first, (second.0, Direction::NonIncreasing), |v| v.2),
+		   (21, vec![7, 4, 6, 5]));
+    }
+
+    #[test]
+    fn test_empty() {
+	let items: [(u8, u8); 0] = [];
+	assert_eq!(longest_chain(&items, (|v| v.0, Direction::Increasing), (|v| v.1, Direction::Increasing)), vec![]);
+    }
+}
@@ -1,49 +1,222 @@
-use std::io::Read;
+use std::io::{BufRead, Read};
+
+use elephants::{chain_partition, dominance_chain, heaviest_chain, Chains, Direction, OnlineChain};
 
 #[derive(PartialEq, Eq, Debug)]
 struct Input {
     index: usize,
     weight: u64,
     iq: u64,
+    /// Optional third column, each elephant is worth one by default.
+    value: u64,
+}
+
+/// Numbers of a line, `line` is only used in errors.
+fn parse_numbers(line: usize, value: &str) -> Result<Vec<u64>, String> {
+    value.split_whitespace()
+	.map(|v| v.parse::<u64>())
+	.collect::<Result<Vec<_>, _>>()
+	.map_err(|e| format!("Coldn't parse numbers on line {}: {}.", line, e))
 }
 
-impl TryFrom<(usize, &str)> for Input {
+/// Elephant number `index` from a line numbered `line` in errors.
+impl TryFrom<(usize, usize, &str)> for Input {
     type Error = String;
 
-    fn try_from(value: (usize, &str)) -> Result<Self, Self::Error> {
-	let numbers = value.1.split_whitespace()
-	    .map(|v| v.parse::<u64>())
-	    .collect::<Result<Vec<_>, _>>()
-	    .map_err(|e| format!("Coldn't parse numbers on line {}: {}.",
-				 value.0, e))?;
-	if numbers.len() != 2 {
-	    return Err(format!("Expected 2 numbers on line {}, found {}.",
-			       value.0, numbers.len()));
+    fn try_from((index, line, value): (usize, usize, &str)) -> Result<Self, Self::Error> {
+	let numbers = parse_numbers(line, value)?;
+	if numbers.len() != 2 && numbers.len() != 3 {
+	    return Err(format!("Expected 2 or 3 numbers on line {}, found {}.",
+			       line, numbers.len()));
 	}
 
 	Ok(Self {
-	    index: value.0,
+	    index,
 	    weight: numbers[0],
 	    iq: numbers[1],
+	    value: numbers.get(2).copied().unwrap_or(1),
 	})
     }
 }
 
-impl PartialOrd for Input {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-	self.weight.partial_cmp(&other.weight).and(other.iq.partial_cmp(&self.iq))
+/// Header line like "weight+ iq- age": the suffix gives the direction of a column,
+/// "+" and "-" are strict, "+=" and "-=" are not, columns without one are skipped.
+#[derive(PartialEq, Eq, Debug)]
+struct Header {
+    columns: Vec<Option<Direction>>,
+}
+
+impl TryFrom<&str> for Header {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+	let columns = value.split_whitespace()
+	    .map(|column| {
+		let direction = [("+=", Direction::NonDecreasing), ("-=", Direction::NonIncreasing),
+				 ("+", Direction::Increasing), ("-", Direction::Decreasing)]
+		    .into_iter()
+		    .find(|(suffix, _)| column.ends_with(suffix));
+
+		match direction {
+		    Some((suffix, _)) if column.len() == suffix.len() =>
+			Err(format!("Expected column name before \"{}\".", suffix)),
+		    direction => Ok(direction.map(|v| v.1)),
+		}
+	    })
+	    .collect::<Result<Vec<_>, _>>()?;
+
+	if columns.iter().all(Option::is_none) {
+	    return Err("Expected at least one column with direction in header.".into());
+	}
+
+	Ok(Self { columns })
     }
 }
 
-impl Ord for Input {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-	self.partial_cmp(&other).unwrap()
+impl Header {
+    fn directions(&self) -> Vec<Direction> {
+	self.columns.iter().flatten().copied().collect()
+    }
+
+    /// Values of the columns with directions.
+    fn parse(&self, line: usize, value: &str) -> Result<Vec<u64>, String> {
+	let numbers = parse_numbers(line, value)?;
+	if numbers.len() != self.columns.len() {
+	    return Err(format!("Expected {} numbers on line {}, found {}.",
+			       self.columns.len(), line, numbers.len()));
+	}
+
+	Ok(numbers.into_iter()
+	   .zip(&self.columns)
+	   .filter_map(|(v, column)| column.map(|_| v))
+	   .collect())
     }
 }
 
+/// Whether the line is a header: one of its columns has a direction suffix.
+fn is_header(value: &str) -> bool {
+    value.split_whitespace().any(|v| v.ends_with(['+', '-']) || v.ends_with("+=") || v.ends_with("-="))
+}
 
+/// Record numbers of the longest chain over the columns chosen by the header line,
+/// records are numbered by their position after the header.
+fn header_chain(lines: &[(usize, &str)]) -> Result<Vec<usize>, String> {
+    let header = Header::try_from(lines[0].1)?;
+    let points = lines[1..].iter()
+	.map(|&(line, v)| header.parse(line, v))
+	.collect::<Result<Vec<_>, _>>()?;
+
+    Ok(dominance_chain(&points, &header.directions()))
+}
+
+/// Indices (`Input::index`) of the longest chain with strictly increasing weight
+/// and strictly decreasing IQ, ordered from the lightest elephant.
+fn longest_chain(inputs: &[Input]) -> Vec<usize> {
+    elephants::longest_chain(
+	inputs,
+	(|v: &Input| v.weight, Direction::Increasing),
+	(|v: &Input| v.iq, Direction::Decreasing),
+    )
+	.into_iter()
+	.map(|idx| inputs[idx].index)
+	.collect()
+}
+
+/// All longest chains of the elephants in the same order as `longest_chain`.
+fn chains(inputs: &[Input]) -> Chains<u64, u64> {
+    Chains::new(
+	inputs,
+	(|v: &Input| v.weight, Direction::Increasing),
+	(|v: &Input| v.iq, Direction::Decreasing),
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// Any longest chain.
+    Longest,
+    /// Length and number of the longest chains.
+    Count,
+    /// Every longest chain, one per line.
+    All,
+    /// Longest chain smallest by original indices.
+    Smallest,
+    /// Chain with the largest total value, printed after the value.
+    Heaviest,
+    /// Fewest chains covering all elephants, one "id: elephants" line per chain.
+    Partition,
+    /// Longest chain length after each elephant read, a "?" line prints the chain itself.
+    Online,
+}
+
+impl TryFrom<Option<&str>> for Mode {
+    type Error = String;
+
+    fn try_from(value: Option<&str>) -> Result<Self, Self::Error> {
+	match value {
+	    None => Ok(Mode::Longest),
+	    Some("--count") => Ok(Mode::Count),
+	    Some("--all") => Ok(Mode::All),
+	    Some("--smallest") => Ok(Mode::Smallest),
+	    Some("--heaviest") => Ok(Mode::Heaviest),
+	    Some("--partition") => Ok(Mode::Partition),
+	    Some("--online") => Ok(Mode::Online),
+	    Some(v) => Err(format!("Unknown argument \"{}\".", v)),
+	}
+    }
+}
+
+/// Weight and IQ of an elephant in online mode, where values are not supported.
+fn online_input(line: usize, value: &str) -> Result<(u64, u64), String> {
+    match parse_numbers(line, value)?[..] {
+	[weight, iq] => Ok((weight, iq)),
+	ref numbers => Err(format!("Expected 2 numbers on line {}, found {}.", line, numbers.len())),
+    }
+}
+
+/// Reads elephants as they come, without waiting for the end of input.
+/// Elephants are numbered in the order they are read, "?" and blank lines are not counted,
+/// and errors refer to elephant lines by the same numbers.
+fn online(input: impl BufRead) -> Result<(), String> {
+    let mut chain = OnlineChain::new(Direction::Increasing, Direction::Decreasing);
+    let mut read = 0;
+
+    for line in input.lines() {
+	let line = line.map_err(|e| format!("Got an IO error: {}", e))?;
+
+	match line.trim() {
+	    "" => (),
+	    "?" => {
+		println!("{}", chain.length());
+		chain.witness().iter().for_each(|&v| println!("{}", v + 1));
+	    },
+	    line => {
+		read += 1;
+		let (weight, iq) = online_input(read, line)?;
+		println!("{}", chain.insert(weight, iq));
+	    },
+	}
+    }
+
+    Ok(())
+}
 
 fn main() {
+    let mode = match Mode::try_from(std::env::args().nth(1).as_deref()) {
+	Ok(v) => v,
+	Err(e) => {
+	    eprintln!("{}", e);
+	    return;
+	},
+    };
+
+    if mode == Mode::Online {
+	if let Err(e) = online(std::io::stdin().lock()) {
+	    eprintln!("{}", e);
+	}
+	return;
+    }
+
     let mut data = String::new();
 
     // <Is this a GoLang now?>.jpg
@@ -52,13 +225,39 @@ fn main() {
 	return;
     }
 
-    let inputs = data
-	.split_terminator("\n")
+    // Lines are numbered from one, elephants by their position among the non-blank lines.
+    let lines = data
+	.split_terminator('\n')
         .enumerate()
-        .map(|(idx, v)| (idx, v.trim()).try_into())
+	.map(|(idx, v)| (idx + 1, v.trim()))
+	.filter(|(_, v)| !v.is_empty())
+	.collect::<Vec<_>>();
+
+    let has_header = lines.first().is_some_and(|(_, v)| is_header(v));
+
+    if has_header {
+	let chain = match mode {
+	    Mode::Longest => header_chain(&lines),
+	    _ => Err("Expected no header for anything but the longest chain.".into()),
+	};
+
+	match chain {
+	    Ok(chain) => {
+		println!("{}", chain.len());
+		chain.iter().for_each(|&idx| println!("{}", idx + 1));
+	    },
+	    Err(e) => eprintln!("{}", e),
+	}
+	return;
+    }
+
+    let inputs = lines
+	.into_iter()
+	.enumerate()
+        .map(|(index, (line, v))| Input::try_from((index, line, v)))
         .collect::<Result<Vec<Input>, _>>();
 
-    let mut inputs = match inputs {
+    let inputs = match inputs {
 	Ok(v) => v,
 	Err(e) => {
 	    eprintln!("{}", e);
@@ -66,29 +265,123 @@ fn main() {
 	},
     };
 
-    inputs.sort_unstable();
+    let chain = match mode {
+	Mode::Longest => longest_chain(&inputs),
+	Mode::Smallest => chains(&inputs)
+	    .smallest()
+	    .unwrap_or_default()
+	    .into_iter()
+	    .map(|idx| inputs[idx].index)
+	    .collect(),
+	Mode::Heaviest => {
+	    let (value, chain) = heaviest_chain(
+		&inputs,
+		(|v: &Input| v.weight, Direction::Increasing),
+		(|v: &Input| v.iq, Direction::Decreasing),
+		|v| v.value,
+	    );
+	    println!("{}", value);
+	    chain.into_iter().map(|idx| inputs[idx].index).collect()
+	},
+	Mode::Online => unreachable!("online mode reads its input by itself"),
+	Mode::Count => {
+	    let chains = chains(&inputs);
+	    println!("{}\n{}", chains.length(), chains.count());
+	    return;
+	},
+	Mode::Partition => {
+	    let chains = chain_partition(
+		&inputs,
+		(|v: &Input| v.weight, Direction::Increasing),
+		(|v: &Input| v.iq, Direction::Decreasing),
+	    );
 
-    let mut longest_idxs = vec![];
+	    println!("{}", chains.len());
+	    chains.iter().enumerate().for_each(|(id, chain)| {
+		let chain = chain.iter().map(|&idx| (inputs[idx].index + 1).to_string()).collect::<Vec<_>>();
+		println!("{}: {}", id + 1, chain.join(" "));
+	    });
+	    return;
+	},
+	Mode::All => {
+	    chains(&inputs).iter().for_each(|chain| {
+		let chain = chain.iter().map(|&idx| (inputs[idx].index + 1).to_string()).collect::<Vec<_>>();
+		println!("{}", chain.join(" "));
+	    });
+	    return;
+	},
+    };
 
-    for idx in 0..(inputs.len() - 1 - longest_idxs.len()) {
-	let mut idxs = Vec::with_capacity(inputs.len());
-	idxs.push(idx);
-	let mut prev = &inputs[idx];
+    println!("{}", chain.len());
+    chain.iter()
+	.for_each(|&idx| println!("{}", idx + 1));
+}
 
-	for i in idx + 1..inputs.len() - 1 {
-	    let cur = &inputs[i];
-	    if prev.weight < cur.weight && prev.iq > cur.iq {
-		idxs.push(i);
-		prev = cur;
-	    }
-	}
+#[cfg(test)]
+mod test {
+    use elephants::Direction;
 
-	if idxs.len() > longest_idxs.len() {
-	    longest_idxs = idxs;
-	}
+    use crate::{header_chain, is_header, longest_chain, online_input, Header, Input};
+
+    fn inputs(data: &[(u64, u64)]) -> Vec<Input> {
+	data.iter()
+	    .enumerate()
+	    .map(|(index, &(weight, iq))| Input { index, weight, iq, value: 1 })
+	    .collect()
     }
 
-    println!("{}", longest_idxs.len());
-    longest_idxs.iter()
-	.for_each(|&idx| println!("{}", inputs[idx].index + 1));
+    #[test]
+    fn test_longest_chain() {
+	let data = inputs(&[(6008, 1300), (6000, 2100), (500, 2000), (1000, 4000),
+			    (1100, 3000), (6000, 2000), (8000, 1400), (6000, 1200), (2000, 1900)]);
+	let chain = longest_chain(&data);
+
+	assert_eq!(chain.len(), 4);
+	assert!(chain.windows(2).all(|v| data[v[0]].weight < data[v[1]].weight
+				       && data[v[0]].iq > data[v[1]].iq));
+    }
+
+    #[test]
+    fn test_header() {
+	let header = Header::try_from("weight+ name iq-= age+").unwrap();
+
+	assert_eq!(header.directions(), vec![Direction::Increasing, Direction::NonIncreasing, Direction::Increasing]);
+	assert_eq!(header.parse(1, "10 7 20 30"), Ok(vec![10, 20, 30]));
+	assert!(header.parse(1, "10 20 30").is_err());
+	assert!(Header::try_from("weight iq").is_err());
+	assert!(Header::try_from("weight+ -").is_err());
+
+	let lines = [(1, "w+ iq- age+"), (2, "1 5 1"), (3, "2 4 5"), (4, "3 3 2"), (5, "4 2 3")];
+	assert_eq!(header_chain(&lines), Ok(vec![0, 2, 3]));
+
+	// Blank lines are already dropped, records keep their positions after the header.
+	let lines = [(1, "w+ iq- age+"), (3, "1 5 1"), (4, "2 4 5"), (5, "3 3 2"), (6, "4 2 3")];
+	assert_eq!(header_chain(&lines), Ok(vec![0, 2, 3]));
+	assert_eq!(header_chain(&[(1, "w+ iq-"), (2, "1 5"), (3, "2")]), Err("Expected 2 numbers on line 3, found 1.".into()));
+
+	assert!(is_header("weight iq-="));
+	assert!(!is_header("a 5"));
+    }
+
+    #[test]
+    fn test_online_input() {
+	assert_eq!(online_input(3, "10 20"), Ok((10, 20)));
+	assert_eq!(online_input(3, "10 20 5"), Err("Expected 2 numbers on line 3, found 3.".into()));
+	assert_eq!(online_input(4, "10"), Err("Expected 2 numbers on line 4, found 1.".into()));
+	assert!(online_input(5, "10 x").is_err_and(|e| e.contains("line 5")));
+    }
+
+    #[test]
+    fn test_parse_input() {
+	assert_eq!(Input::try_from((1, 3, "10 20")), Ok(Input { index: 1, weight: 10, iq: 20, value: 1 }));
+	assert_eq!(Input::try_from((1, 3, "10 20 5")).map(|v| v.value), Ok(5));
+	assert!(Input::try_from((1, 2, "x 3")).is_err_and(|e| e.contains("line 2")));
+    }
+
+    #[test]
+    fn test_longest_chain_edges() {
+	assert_eq!(longest_chain(&[]), vec![]);
+	assert_eq!(longest_chain(&inputs(&[(1, 5), (1, 4), (1, 6)])).len(), 1);
+	assert_eq!(longest_chain(&inputs(&[(3, 1), (2, 2), (1, 3)])), vec![2, 1, 0]);
+    }
 }
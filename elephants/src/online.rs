@@ -0,0 +1,152 @@
+use crate::Direction;
+
+/// Longest chain kept up to date while items are inserted one by one in any order.
+///
+/// Every item keeps the lengths of the longest chains ending and starting at it. A new
+/// item raises any of them by at most one, so only the raised ones are passed on.
+/// An insert takes O(n) comparisons plus, for every raised item, one per raised item
+/// of its length: O(n^2) at worst, but close to linear when few chains grow.
+pub struct OnlineChain<A, B> {
+    keys: Vec<(A, B)>,
+    directions: (Direction, Direction),
+    /// Insertion numbers in the order chains follow, equal items by insertion.
+    order: Vec<usize>,
+    /// Length of the longest chain ending at each item.
+    ending: Vec<usize>,
+    /// Length of the longest chain starting at each item.
+    starting: Vec<usize>,
+    witness: Vec<usize>,
+}
+
+/// Raises `lengths` of the items in `order`, which may only follow earlier ones, past
+/// the inserted `item` at its start. Raised items are kept by their new length.
+fn raise(lengths: &mut [usize], item: usize, order: &[usize], follows: impl Fn(usize, usize) -> bool) {
+    let mut raised = vec![vec![]; lengths[item] + 1];
+    raised[lengths[item]].push(item);
+
+    for &idx in order {
+	let length = lengths[idx];
+	if raised.get(length).is_some_and(|v| v.iter().any(|&prev| follows(prev, idx))) {
+	    lengths[idx] += 1;
+	    match raised.get_mut(length + 1) {
+		Some(v) => v.push(idx),
+		None => raised.push(vec![idx]),
+	    }
+	}
+    }
+}
+
+impl<A: Ord, B: Ord> OnlineChain<A, B> {
+    pub fn new(first: Direction, second: Direction) -> Self {
+	Self {
+	    keys: vec![],
+	    directions: (first, second),
+	    order: vec![],
+	    ending: vec![],
+	    starting: vec![],
+	    witness: vec![],
+	}
+    }
+
+    /// Whether item `next` may directly follow item `prev` in a chain. Items equal
+    /// under non-strict directions fit on both sides, so the earlier one goes first.
+    fn follows(&self, prev: usize, next: usize) -> bool {
+	let fits = |l: usize, r: usize| self.directions.0.fits(&self.keys[l].0, &self.keys[r].0)
+	    && self.directions.1.fits(&self.keys[l].1, &self.keys[r].1);
+	fits(prev, next) && (prev < next || !fits(next, prev))
+    }
+
+    /// Adds an item and returns the length of the longest chain so far.
+    pub fn insert(&mut self, first: A, second: B) -> usize {
+	let item = self.keys.len();
+	self.keys.push((first, second));
+
+	let (first_dir, second_dir) = self.directions;
+	let pos = self.order.partition_point(|&idx| first_dir
+	    .cmp(&self.keys[idx].0, &self.keys[item].0)
+	    .then(second_dir.cmp(&self.keys[idx].1, &self.keys[item].1))
+	    .is_le());
+	self.order.insert(pos, item);
+
+	let (before, after) = (&self.order[..pos], &self.order[pos + 1..]);
+	let ending = before.iter().filter(|&&idx| self.follows(idx, item)).map(|&idx| self.ending[idx]).max();
+	let starting = after.iter().filter(|&&idx| self.follows(item, idx)).map(|&idx| self.starting[idx]).max();
+	self.ending.push(ending.unwrap_or(0) + 1);
+	self.starting.push(starting.unwrap_or(0) + 1);
+
+	let mut ending = std::mem::take(&mut self.ending);
+	raise(&mut ending, item, after, |prev, next| self.follows(prev, next));
+	self.ending = ending;
+
+	// Walking backwards, chains starting at an item are raised through the ones after it.
+	let before = before.iter().rev().copied().collect::<Vec<_>>();
+	let mut starting = std::mem::take(&mut self.starting);
+	raise(&mut starting, item, &before, |next, prev| self.follows(prev, next));
+	self.starting = starting;
+
+	// A longer chain has to pass through the new item.
+	if self.ending[item] + self.starting[item] - 1 > self.witness.len() {
+	    self.witness = self.chain_through(pos);
+	}
+
+	self.witness.len()
+    }
+
+    /// Longest chain through the item at `pos` in `order`, walking out from it.
+    fn chain_through(&self, pos: usize) -> Vec<usize> {
+	let item = self.order[pos];
+
+	let mut res = vec![item];
+	for &idx in self.order[..pos].iter().rev() {
+	    let last = *res.last().unwrap();
+	    if self.ending[idx] + 1 == self.ending[last] && self.follows(idx, last) {
+		res.push(idx);
+	    }
+	}
+	res.reverse();
+
+	for &idx in &self.order[pos + 1..] {
+	    let last = *res.last().unwrap();
+	    if self.starting[idx] + 1 == self.starting[last] && self.follows(last, idx) {
+		res.push(idx);
+	    }
+	}
+
+	res
+    }
+
+    /// Length of the longest chain so far.
+    pub fn length(&self) -> usize {
+	self.witness.len()
+    }
+
+    /// Longest chain so far as insertion numbers of its items, ordered along the chain.
+    pub fn witness(&self) -> &[usize] {
+	&self.witness
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{longest_chain, Direction, OnlineChain};
+
+    #[test]
+    fn test_online_chain() {
+	let items = (0..120u64).map(|v| (v * 37 % 23, v * 11 % 17)).collect::<Vec<_>>();
+
+	for (first, second) in [(Direction::Increasing, Direction::Decreasing),
+				(Direction::NonDecreasing, Direction::NonIncreasing)] {
+	    let mut online = OnlineChain::new(first, second);
+
+	    for (count, &(weight, iq)) in items.iter().enumerate() {
+		let length = online.insert(weight, iq);
+		let expected = longest_chain(&items[..=count], (|v: &(u64, u64)| v.0, first), (|v: &(u64, u64)| v.1, second));
+
+		assert_eq!(length, expected.len());
+		assert_eq!(online.witness().len(), length);
+		assert!(online.witness().windows(2).all(|v| first.fits(&items[v[0]].0, &items[v[1]].0)
+							 && second.fits(&items[v[0]].1, &items[v[1]].1)));
+	    }
+	}
+    }
+}
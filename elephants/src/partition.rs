@@ -0,0 +1,118 @@
+use std::cmp::Ordering;
+
+use crate::Direction;
+
+/// Dense ranks of items under `cmp`, equal items get equal ranks.
+fn dense_ranks(len: usize, cmp: impl Fn(usize, usize) -> Ordering) -> Vec<usize> {
+    let mut order = (0..len).collect::<Vec<_>>();
+    order.sort_by(|&l, &r| cmp(l, r));
+
+    let mut res = vec![0; len];
+    for pos in 1..order.len() {
+	res[order[pos]] = res[order[pos - 1]] + cmp(order[pos - 1], order[pos]).is_ne() as usize;
+    }
+    res
+}
+
+/// Fewest chains covering every item once, each as positions in `items` ordered along it,
+/// where both keys change in their directions. Takes O(n log n).
+pub fn chain_partition<T, A: Ord, B: Ord>(
+    items: &[T],
+    (first, first_dir): (impl Fn(&T) -> A, Direction),
+    (second, second_dir): (impl Fn(&T) -> B, Direction),
+) -> Vec<Vec<usize>> {
+    let keys = items.iter().map(|v| (first(v), second(v))).collect::<Vec<_>>();
+
+    // A non-strict key is the same as a strict one tie-broken by the other key (and by
+    // position when both are non-strict), so chains only need both ranks to increase.
+    let both = !first_dir.is_strict() && !second_dir.is_strict();
+    let first = dense_ranks(items.len(), |l, r| {
+	let res = first_dir.cmp(&keys[l].0, &keys[r].0);
+	match first_dir.is_strict() {
+	    true => res,
+	    false => res.then(second_dir.cmp(&keys[l].1, &keys[r].1)).then(if both { l.cmp(&r) } else { Ordering::Equal }),
+	}
+    });
+    let second = dense_ranks(items.len(), |l, r| {
+	let res = second_dir.cmp(&keys[l].1, &keys[r].1);
+	match second_dir.is_strict() {
+	    true => res,
+	    false => res.then(first_dir.cmp(&keys[l].0, &keys[r].0)).then(if both { l.cmp(&r) } else { Ordering::Equal }),
+	}
+    });
+
+    // Equal first ranks go by descending second rank, so none of them can share a chain.
+    let mut order = (0..items.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&idx| (first[idx], std::cmp::Reverse(second[idx])));
+
+    // Chains by descending second rank of their last items: each item goes to the first
+    // chain it can continue, the one with the highest end below it.
+    let mut chains: Vec<Vec<usize>> = vec![];
+    let mut ends: Vec<usize> = vec![];
+
+    for idx in order {
+	let pos = ends.partition_point(|&end| second[end] >= second[idx]);
+	match ends.get_mut(pos) {
+	    Some(end) => {
+		*end = idx;
+		chains[pos].push(idx);
+	    },
+	    None => {
+		ends.push(idx);
+		chains.push(vec![idx]);
+	    },
+	}
+    }
+
+    chains
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{chain_partition, Direction};
+
+    /// Fewest chains covering the items, by dynamic programming over subsets.
+    fn brute_force(items: &[(u8, u8)], first: Direction, second: Direction) -> usize {
+	let is_chain = |mask: usize| {
+	    let mut chain = (0..items.len()).filter(|i| mask >> i & 1 == 1).map(|i| items[i]).collect::<Vec<_>>();
+	    chain.sort_by(|l, r| first.cmp(&l.0, &r.0).then(second.cmp(&l.1, &r.1)));
+	    chain.windows(2).all(|v| first.fits(&v[0].0, &v[1].0) && second.fits(&v[0].1, &v[1].1))
+	};
+
+	let full = (1 << items.len()) - 1;
+	let mut best = vec![usize::MAX; full + 1];
+	best[0] = 0;
+	for mask in 1..=full {
+	    let mut sub = mask;
+	    while sub > 0 {
+		if best[mask ^ sub] != usize::MAX && is_chain(sub) {
+		    best[mask] = best[mask].min(best[mask ^ sub] + 1);
+		}
+		sub = (sub - 1) & mask;
+	    }
+	}
+	best[full]
+    }
+
+    #[test]
+    fn test_chain_partition() {
+	let items = [(1, 3), (2, 2), (2, 3), (3, 1), (3, 3), (1, 1), (2, 2), (0, 2)];
+	let directions = [Direction::Increasing, Direction::NonDecreasing,
+			  Direction::Decreasing, Direction::NonIncreasing];
+
+	for first in directions {
+	    for second in directions {
+		let chains = chain_partition(&items, (|v: &(u8, u8)| v.0, first), (|v: &(u8, u8)| v.1, second));
+
+		let mut covered = chains.concat();
+		covered.sort_unstable();
+		assert_eq!(covered, (0..items.len()).collect::<Vec<_>>());
+		assert!(chains.iter().flat_map(|v| v.windows(2))
+			.all(|v| first.fits(&items[v[0]].0, &items[v[1]].0) && second.fits(&items[v[0]].1, &items[v[1]].1)));
+		assert_eq!(chains.len(), brute_force(&items, first, second), "{:?} {:?}", first, second);
+	    }
+	}
+
+	assert!(chain_partition(&[] as &[(u8, u8)], (|v| v.0, Direction::Increasing), (|v| v.1, Direction::Increasing)).is_empty());
+    }
+}